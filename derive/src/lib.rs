@@ -0,0 +1,231 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Fields, GenericArgument, Ident, LitStr,
+    PathArguments, Result, Type,
+};
+
+/// Derives `open_all`, per-field cursor accessors and type aliases for a
+/// struct whose fields are all `Database<'e, K, V, L>`. `open_all` opens the
+/// databases through `TypedEnv::open_db`, so its type registry applies.
+///
+/// Each field may carry `#[db(...)]` with `name = "..."` (defaults to the
+/// field name) and any of the flags `dupsort`, `dupfixed`, `integer_key`,
+/// `integer_dup`, `reverse_key` and `reverse_dup`. The duplicate flags are
+/// checked against the field's layout at compile time.
+#[proc_macro_derive(Schema, attributes(db))]
+pub fn derive_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct DbField {
+    ident: Ident,
+    name: LitStr,
+    flags: Vec<Ident>,
+    key: Type,
+    value: Type,
+    layout: Type,
+}
+
+const FLAGS: &[(&str, &str)] = &[
+    ("dupsort", "DUPSORT"),
+    ("dupfixed", "DUPFIXED"),
+    ("integer_key", "INTEGERKEY"),
+    ("integer_dup", "INTEGERDUP"),
+    ("reverse_key", "REVERSEKEY"),
+    ("reverse_dup", "REVERSEDUP"),
+];
+
+fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let vis = &input.vis;
+
+    let mut lifetimes = input.generics.lifetimes();
+    let env = match (lifetimes.next(), lifetimes.next()) {
+        (Some(env), None) if input.generics.type_params().next().is_none() => &env.lifetime,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.generics,
+                "Schema structs must have exactly one lifetime parameter, the environment's",
+            ))
+        }
+    };
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(ident, "Schema requires named fields")),
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                ident,
+                "Schema can only be derived for structs",
+            ))
+        }
+    };
+    let fields = fields.iter().map(parse_field).collect::<Result<Vec<_>>>()?;
+
+    let opens = fields.iter().map(|f| {
+        let DbField {
+            ident,
+            name,
+            flags,
+            key,
+            value,
+            layout,
+        } = f;
+        quote! {
+            #ident: env.open_db::<#key, #value, #layout>(
+                ::std::option::Option::Some(#name),
+                &::lmdb_zero_typed::__private::lmdb_zero::DatabaseOptions::new(
+                    ::lmdb_zero_typed::__private::lmdb_zero::db::CREATE
+                        #(| ::lmdb_zero_typed::__private::lmdb_zero::db::#flags)*
+                ),
+            )?
+        }
+    });
+
+    let layout_checks = fields.iter().map(|f| {
+        let layout = &f.layout;
+        let has = |flag: &str| f.flags.iter().any(|i| i == flag);
+        let bound = if has("DUPFIXED") {
+            quote!(::lmdb_zero_typed::LayoutDupfixed)
+        } else if has("DUPSORT") {
+            quote!(::lmdb_zero_typed::LayoutDupsort)
+        } else {
+            quote!(::lmdb_zero_typed::LayoutNoDuplicates)
+        };
+        quote! {{
+            fn layout_matches_flags<L: #bound>() {}
+            layout_matches_flags::<#layout>();
+        }}
+    });
+
+    let cursors = fields.iter().map(|f| {
+        let DbField {
+            ident,
+            key,
+            value,
+            layout,
+            ..
+        } = f;
+        let method = format_ident!("{}_cursor", ident);
+        quote! {
+            #vis fn #method<'txn, 'db>(
+                &'db self,
+                txn: &'txn ::lmdb_zero_typed::ConstTransaction<#env>,
+            ) -> ::lmdb_zero_typed::error::Result<::lmdb_zero_typed::Cursor<'txn, 'db, #key, #value, #layout>>
+            where
+                #env: 'db,
+            {
+                txn.cursor(&self.#ident)
+            }
+        }
+    });
+
+    let aliases = fields.iter().map(|f| {
+        let DbField {
+            ident,
+            key,
+            value,
+            layout,
+            ..
+        } = f;
+        let camel = camel_case(ident);
+        let db = format_ident!("{}Db", camel);
+        let cursor = format_ident!("{}Cursor", camel);
+        quote! {
+            #vis type #db<'e> = ::lmdb_zero_typed::Database<'e, #key, #value, #layout>;
+            #vis type #cursor<'t, 'd> = ::lmdb_zero_typed::Cursor<'t, 'd, #key, #value, #layout>;
+        }
+    });
+
+    Ok(quote! {
+        impl<#env> #ident<#env> {
+            #vis fn open_all(
+                env: &#env ::lmdb_zero_typed::TypedEnv,
+            ) -> ::lmdb_zero_typed::error::Result<Self> {
+                #(#layout_checks)*
+                ::std::result::Result::Ok(#ident {
+                    #(#opens,)*
+                })
+            }
+
+            #(#cursors)*
+        }
+
+        #(#aliases)*
+    })
+}
+
+fn parse_field(field: &syn::Field) -> Result<DbField> {
+    let ident = field.ident.clone().unwrap();
+    let mut name = LitStr::new(&ident.to_string(), ident.span());
+    let mut flags = Vec::new();
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("db")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = meta.value()?.parse()?;
+                return Ok(());
+            }
+            for (attr, flag) in FLAGS {
+                if meta.path.is_ident(attr) {
+                    flags.push(Ident::new(flag, Span::call_site()));
+                    return Ok(());
+                }
+            }
+            Err(meta.error("unknown db attribute"))
+        })?;
+    }
+
+    let [key, value, layout] = database_args(&field.ty)?;
+    Ok(DbField {
+        ident,
+        name,
+        flags,
+        key,
+        value,
+        layout,
+    })
+}
+
+/// Extracts `K`, `V` and `L` from a `Database<'e, K, V, L>` type.
+fn database_args(ty: &Type) -> Result<[Type; 3]> {
+    let error = || Error::new_spanned(ty, "expected a field of type Database<'e, K, V, L>");
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last().ok_or_else(error)?,
+        _ => return Err(error()),
+    };
+    if segment.ident != "Database" {
+        return Err(error());
+    }
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => &args.args,
+        _ => return Err(error()),
+    };
+    let types: Vec<Type> = args
+        .iter()
+        .filter_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty.clone()),
+            _ => None,
+        })
+        .collect();
+    types.try_into().map_err(|_| error())
+}
+
+fn camel_case(ident: &Ident) -> String {
+    ident
+        .to_string()
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            std::iter::once(first).chain(chars).collect::<String>()
+        })
+        .collect()
+}
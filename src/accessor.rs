@@ -1,7 +1,17 @@
-use lmdb_zero::traits::{AsLmdbBytes, FromLmdbBytes, FromReservedLmdbBytes};
-use lmdb_zero::Result;
+use std::borrow::{Borrow, Cow};
+use std::cmp::Ordering;
+use std::mem::size_of;
 
-use crate::{Database, Layout};
+use lmdb_zero::traits::{
+    AsLmdbBytes, FromLmdbBytes, FromReservedLmdbBytes, LmdbRawIfUnaligned, LmdbResultExt,
+};
+use lmdb_zero::{del, put, unaligned, Unaligned};
+
+use crate::error::{Context, DecodeContext, EncodeContext, Error, Result, ResultExt, WithContext};
+use crate::{
+    Codec, CodecOwned, ConstTransaction, Cursor, CursorAsXAs, CursorAsXAsDupsort, Database, Layout,
+    LayoutDupsort, LayoutNoDuplicates,
+};
 
 #[derive(Debug)]
 pub enum ConstAccessor<'txn> {
@@ -13,17 +23,133 @@ pub enum ConstAccessor<'txn> {
 pub struct WriteAccessor<'env>(ConstAccessor<'env>);
 
 impl<'txn> ConstAccessor<'txn> {
-    pub fn get<'env, K, V, L>(&self, db: &Database<'env, K, V, L>, key: &K) -> Result<&V>
+    pub fn get<'env, K, V, L>(
+        &self,
+        db: &Database<'env, K, V, L>,
+        key: &K::Item,
+    ) -> Result<V::Decoded<'_>>
     where
-        K: AsLmdbBytes + ?Sized,
-        V: FromLmdbBytes + ?Sized,
+        K: Codec + ?Sized,
+        V: Codec + ?Sized,
         'env: 'txn,
         L: Layout,
     {
-        match self {
-            ConstAccessor::Read(access) => access.get(&db.0, key),
-            ConstAccessor::Write(access) => access.get(&db.0, key),
+        let name = db.name_arc();
+        let key = K::encode(key).encode_context("get", name)?;
+        let val = self
+            .as_lmdb()
+            .get::<[u8], [u8]>(&db.0, &key)
+            .context("get", name)?;
+        V::decode(val).decode_context("get", name)
+    }
+
+    /// Like `get`, but returns `Ok(None)` if `key` is not present.
+    #[inline]
+    pub fn get_opt<'env, K, V, L>(
+        &self,
+        db: &Database<'env, K, V, L>,
+        key: &K::Item,
+    ) -> Result<Option<V::Decoded<'_>>>
+    where
+        K: Codec + ?Sized,
+        V: Codec + ?Sized,
+        'env: 'txn,
+        L: Layout,
+    {
+        self.get(db, key).to_opt()
+    }
+
+    /// Like `get`, but detaches the value from the transaction.
+    #[inline]
+    pub fn get_owned<'env, K, V, L>(
+        &self,
+        db: &Database<'env, K, V, L>,
+        key: &K::Item,
+    ) -> Result<V::Owned>
+    where
+        K: Codec + ?Sized,
+        V: CodecOwned + ?Sized,
+        'env: 'txn,
+        L: Layout,
+    {
+        self.get(db, key).map(V::into_owned)
+    }
+
+    /// Looks up all of `keys`, returning their values in the same order.
+    ///
+    /// The keys are sorted and visited with a single cursor, so that lookups
+    /// of nearby keys reuse the pages already found instead of searching from
+    /// the root each time. Input that is already sorted costs little to sort.
+    ///
+    /// `txn` must be the transaction this accessor came from; the cursor has
+    /// to be opened on it, and an accessor can't reach its own transaction.
+    /// Given another transaction, this returns `Error::Lmdb` holding
+    /// `lmdb_zero::Error::Mismatch` once it looks up a key.
+    pub fn get_many<'env, K, V, L, I>(
+        &self,
+        txn: &ConstTransaction<'env>,
+        db: &Database<'env, K, V, L>,
+        keys: I,
+    ) -> Result<Vec<Option<V::Decoded<'_>>>>
+    where
+        K: Codec + ?Sized,
+        V: Codec + ?Sized,
+        L: Layout,
+        I: IntoIterator,
+        I::Item: Borrow<K::Item>,
+        'env: 'txn,
+    {
+        let name = db.name_arc();
+        let keys = keys
+            .into_iter()
+            .map(|key| K::encode(key.borrow()).map(Cow::into_owned))
+            .collect::<lmdb_zero::Result<Vec<_>>>()
+            .encode_context("get_many", name)?;
+
+        let mut cursor = txn.cursor(db)?;
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        order.sort_by(|&a, &b| cursor.cmp_keys(&keys[a], &keys[b]));
+
+        let access = self.as_lmdb();
+        let mut found = vec![None; keys.len()];
+        let mut current: Option<(&[u8], &[u8])> = None;
+        for i in order {
+            let key = &keys[i][..];
+            // Try the next entry before seeking, as dense runs of keys are
+            // common; past the end, every remaining key is missing.
+            if let Some((k, _)) = current {
+                if cursor.cmp_keys(k, key) == Ordering::Less {
+                    current = cursor.0.next(access).to_opt().context("get_many", name)?;
+                    if current.is_none() {
+                        break;
+                    }
+                }
+            }
+            match current {
+                Some((k, _)) if cursor.cmp_keys(k, key) != Ordering::Less => (),
+                _ => {
+                    current = cursor
+                        .0
+                        .seek_range_k(access, key)
+                        .to_opt()
+                        .context("get_many", name)?;
+                }
+            }
+            match current {
+                Some((k, v)) if cursor.cmp_keys(k, key) == Ordering::Equal => found[i] = Some(v),
+                Some(_) => (),
+                None => break,
+            }
         }
+
+        found
+            .into_iter()
+            .map(|val| {
+                val.map(V::decode)
+                    .transpose()
+                    .decode_context("get_many", name)
+            })
+            .collect()
     }
 
     pub fn as_lmdb(&self) -> &lmdb_zero::ConstAccessor<'txn> {
@@ -39,16 +165,21 @@ impl<'txn> WriteAccessor<'txn> {
     pub fn put<K, V, L>(
         &mut self,
         db: &Database<K, V, L>,
-        key: &K,
-        value: &V,
+        key: &K::Item,
+        value: &V::Item,
         flags: lmdb_zero::put::Flags,
     ) -> Result<()>
     where
-        K: AsLmdbBytes + ?Sized,
-        V: AsLmdbBytes + ?Sized,
+        K: Codec + ?Sized,
+        V: Codec + ?Sized,
         L: Layout,
     {
-        self.as_lmdb_mut().put(&db.0, key, value, flags)
+        let name = db.name_arc();
+        let key = K::encode(key).encode_context("put", name)?;
+        let value = V::encode(value).encode_context("put", name)?;
+        self.as_lmdb_mut()
+            .put(&db.0, &*key, &*value, flags)
+            .context("put", name)
     }
 
     #[inline]
@@ -60,60 +191,379 @@ impl<'txn> WriteAccessor<'txn> {
     pub fn put_reserve<K, V, L>(
         &mut self,
         db: &Database<K, V, L>,
-        key: &K,
+        key: &K::Item,
         flags: lmdb_zero::put::Flags,
     ) -> Result<&mut V>
     where
-        K: AsLmdbBytes + ?Sized,
+        K: Codec + ?Sized,
         V: FromReservedLmdbBytes + Sized,
         L: Layout,
     {
-        self.as_lmdb_mut().put_reserve(&db.0, key, flags)
+        let name = db.name_arc();
+        let key = K::encode(key).encode_context("put_reserve", name)?;
+        self.as_lmdb_mut()
+            .put_reserve(&db.0, &*key, flags)
+            .context("put_reserve", name)
     }
 
+    /// # Safety
+    ///
+    /// See `lmdb_zero::WriteAccessor::put_reserve_unsized`.
     #[inline]
     pub unsafe fn put_reserve_unsized<K, V, L>(
         &mut self,
         db: &Database<K, V, L>,
-        key: &K,
+        key: &K::Item,
         size: usize,
         flags: lmdb_zero::put::Flags,
     ) -> Result<&mut V>
     where
-        K: AsLmdbBytes + ?Sized,
+        K: Codec + ?Sized,
         V: FromReservedLmdbBytes + ?Sized,
         L: Layout,
     {
+        let name = db.name_arc();
+        let key = K::encode(key).encode_context("put_reserve_unsized", name)?;
         self.as_lmdb_mut()
-            .put_reserve_unsized(&db.0, key, size, flags)
+            .put_reserve_unsized(&db.0, &*key, size, flags)
+            .context("put_reserve_unsized", name)
     }
 
     #[inline]
-    pub fn del_key<K, V, L>(&mut self, db: &Database<K, V, L>, key: &K) -> Result<()>
+    pub fn del_key<K, V, L>(&mut self, db: &Database<K, V, L>, key: &K::Item) -> Result<()>
     where
-        K: AsLmdbBytes + ?Sized,
+        K: Codec + ?Sized,
         V: ?Sized,
         L: Layout,
     {
-        self.as_lmdb_mut().del_key(&db.0, key)
+        let name = db.name_arc();
+        let key = K::encode(key).encode_context("del_key", name)?;
+        self.as_lmdb_mut()
+            .del_key(&db.0, &*key)
+            .context("del_key", name)
     }
 
     #[inline]
-    pub fn del_item<K, V, L>(&mut self, db: &Database<K, V, L>, key: &K, val: &V) -> Result<()>
+    pub fn del_item<K, V, L>(
+        &mut self,
+        db: &Database<K, V, L>,
+        key: &K::Item,
+        val: &V::Item,
+    ) -> Result<()>
     where
-        K: AsLmdbBytes + ?Sized,
-        V: AsLmdbBytes + ?Sized,
+        K: Codec + ?Sized,
+        V: Codec + ?Sized,
         L: Layout,
     {
-        self.as_lmdb_mut().del_item(&db.0, key, val)
+        let name = db.name_arc();
+        let key = K::encode(key).encode_context("del_item", name)?;
+        let val = V::encode(val).encode_context("del_item", name)?;
+        self.as_lmdb_mut()
+            .del_item(&db.0, &*key, &*val)
+            .context("del_item", name)
     }
 
     #[inline]
     pub fn clear_db<K, V, L>(&mut self, db: &Database<K, V, L>) -> Result<()>
     where
+        K: ?Sized,
+        V: ?Sized,
         L: Layout,
     {
-        self.as_lmdb_mut().clear_db(&db.0)
+        self.as_lmdb_mut()
+            .clear_db(&db.0)
+            .context("clear_db", db.name_arc())
+    }
+
+    /// Replaces the value under `key` with `f(current)`, deleting it if `f`
+    /// returns `None`, and returns the new value.
+    ///
+    /// `update_at` does the same through a cursor, overwriting the value in
+    /// place when its encoded size is unchanged.
+    pub fn update<'env, K, V, L, F>(
+        &mut self,
+        db: &Database<'env, K, V, L>,
+        key: &K::Item,
+        f: F,
+    ) -> Result<Option<V::Owned>>
+    where
+        K: Codec + ?Sized,
+        V: CodecOwned + ?Sized,
+        V::Owned: Borrow<V::Item>,
+        L: Layout + LayoutNoDuplicates,
+        F: FnOnce(Option<V::Decoded<'_>>) -> Option<V::Owned>,
+        'env: 'txn,
+    {
+        let (existed, new) = {
+            let old = self.get_opt(db, key)?;
+            (old.is_some(), f(old))
+        };
+        match &new {
+            Some(val) => self.put(db, key, val.borrow(), put::Flags::empty())?,
+            None if existed => self.del_key(db, key)?,
+            None => (),
+        }
+        Ok(new)
+    }
+
+    /// Like `update`, but looks `key` up with `cursor` and writes back at its
+    /// position, copying over the old value if the new one has the same size.
+    pub fn update_at<K, V, L, F>(
+        &mut self,
+        cursor: &mut Cursor<K, V, L>,
+        key: &K::Item,
+        f: F,
+    ) -> Result<Option<V::Owned>>
+    where
+        K: Codec + ?Sized,
+        V: CodecOwned + ?Sized,
+        V::Owned: Borrow<V::Item>,
+        L: Layout + LayoutNoDuplicates,
+        F: FnOnce(Option<V::Decoded<'_>>) -> Option<V::Owned>,
+    {
+        let name = cursor.db_name().cloned();
+        let name = name.as_ref();
+        let key = K::encode(key).encode_context("update_at", name)?;
+        let (old_len, new) = {
+            let old = cursor
+                .0
+                .seek_k::<[u8], [u8]>(self.as_lmdb(), &key)
+                .to_opt()
+                .context("update_at", name)?;
+            let old_len = old.map(<[u8]>::len);
+            let old = old
+                .map(V::decode)
+                .transpose()
+                .decode_context("update_at", name)?;
+            (old_len, f(old))
+        };
+        let val = match &new {
+            Some(val) => Some(V::encode(val.borrow()).encode_context("update_at", name)?),
+            None => None,
+        };
+
+        let access = self.as_lmdb_mut();
+        match (val, old_len) {
+            (Some(val), Some(len)) if val.len() == len => {
+                // SAFETY: `val` has the length of the stored value, the
+                // cursor was just positioned on that record by `seek_k`, and
+                // the write transaction behind `self` is live while `slot` is.
+                let slot: &mut [u8] = unsafe {
+                    cursor
+                        .0
+                        .overwrite_in_place_unsized(access, &key[..], len, put::Flags::empty())
+                }
+                .context("update_at", name)?;
+                slot.copy_from_slice(&val);
+            }
+            (Some(val), Some(_)) => cursor
+                .0
+                .overwrite(access, &key[..], &val[..], put::Flags::empty())
+                .context("update_at", name)?,
+            (Some(val), None) => cursor
+                .0
+                .put(access, &key[..], &val[..], put::Flags::empty())
+                .context("update_at", name)?,
+            (None, Some(_)) => cursor
+                .0
+                .del(access, del::Flags::empty())
+                .context("update_at", name)?,
+            (None, None) => (),
+        }
+        Ok(new)
+    }
+
+    /// Like `update`, but always stores the value `f` returns.
+    pub fn upsert<'env, K, V, L, F>(
+        &mut self,
+        db: &Database<'env, K, V, L>,
+        key: &K::Item,
+        f: F,
+    ) -> Result<V::Owned>
+    where
+        K: Codec + ?Sized,
+        V: CodecOwned + ?Sized,
+        V::Owned: Borrow<V::Item>,
+        L: Layout + LayoutNoDuplicates,
+        F: FnOnce(Option<V::Decoded<'_>>) -> V::Owned,
+        'env: 'txn,
+    {
+        let new = f(self.get_opt(db, key)?);
+        self.put(db, key, new.borrow(), put::Flags::empty())?;
+        Ok(new)
+    }
+
+    /// Adds `delta` to the integer stored under `key`, treating a missing key
+    /// as zero, and returns the previous value. Fails with `Error::Overflow`
+    /// rather than wrapping.
+    ///
+    /// The stored value may be unaligned, so it is read and written through
+    /// `Unaligned`; the new value is written with `MDB_RESERVE`.
+    /// `fetch_add_at` overwrites it in place through a cursor instead.
+    pub fn fetch_add<K, V, L>(
+        &mut self,
+        db: &Database<K, V, L>,
+        key: &K::Item,
+        delta: V,
+    ) -> Result<V>
+    where
+        K: Codec + ?Sized,
+        V: LmdbRawIfUnaligned + Default + CheckedAdd,
+        L: Layout + LayoutNoDuplicates,
+    {
+        let name = db.name_arc();
+        let key = K::encode(key).encode_context("fetch_add", name)?;
+        let old = self
+            .as_lmdb()
+            .get::<[u8], [u8]>(&db.0, &key)
+            .to_opt()
+            .and_then(read_counter::<V>)
+            .decode_context("fetch_add", name)?
+            .unwrap_or_default();
+
+        let new = add_counter(old, delta).context("fetch_add", name)?;
+        let reserved: &mut [u8] = unsafe {
+            self.as_lmdb_mut().put_reserve_unsized(
+                &db.0,
+                &key[..],
+                size_of::<V>(),
+                put::Flags::empty(),
+            )
+        }
+        .context("fetch_add", name)?;
+        reserved.copy_from_slice(unaligned(&new).as_lmdb_bytes());
+        Ok(old)
+    }
+
+    /// Like `fetch_add`, but looks `key` up with `cursor` and overwrites the
+    /// stored integer in place.
+    pub fn fetch_add_at<K, V, L>(
+        &mut self,
+        cursor: &mut Cursor<K, V, L>,
+        key: &K::Item,
+        delta: V,
+    ) -> Result<V>
+    where
+        K: Codec + ?Sized,
+        V: LmdbRawIfUnaligned + Default + CheckedAdd,
+        L: Layout + LayoutNoDuplicates,
+    {
+        let name = cursor.db_name().cloned();
+        let name = name.as_ref();
+        let key = K::encode(key).encode_context("fetch_add_at", name)?;
+        let old = cursor
+            .0
+            .seek_k::<[u8], [u8]>(self.as_lmdb(), &key)
+            .to_opt()
+            .and_then(read_counter::<V>)
+            .decode_context("fetch_add_at", name)?;
+
+        let new = add_counter(old.unwrap_or_default(), delta).context("fetch_add_at", name)?;
+        let access = self.as_lmdb_mut();
+        let size = size_of::<V>();
+        // SAFETY: a stored counter was read as exactly `size` bytes with the
+        // cursor positioned on it, and the write transaction is live while
+        // `slot` is; a missing one is reserved at that size instead.
+        let slot: &mut [u8] = unsafe {
+            match old {
+                Some(_) => {
+                    cursor
+                        .0
+                        .overwrite_in_place_unsized(access, &key[..], size, put::Flags::empty())
+                }
+                None => cursor
+                    .0
+                    .reserve_unsized(access, &key[..], size, put::Flags::empty()),
+            }
+        }
+        .context("fetch_add_at", name)?;
+        slot.copy_from_slice(unaligned(&new).as_lmdb_bytes());
+        Ok(old.unwrap_or_default())
+    }
+
+    /// Stores `new` under `key`, or deletes it if `new` is `None`, but only if
+    /// the current value encodes to the same bytes as `expected`, with `None`
+    /// meaning absent.
+    ///
+    /// On a mismatch nothing is written and the current value is returned as
+    /// the error.
+    pub fn compare_and_swap<'env, K, V, L>(
+        &mut self,
+        db: &Database<'env, K, V, L>,
+        key: &K::Item,
+        expected: Option<&V::Item>,
+        new: Option<&V::Item>,
+    ) -> Result<std::result::Result<(), Option<V::Owned>>>
+    where
+        K: Codec + ?Sized,
+        V: CodecOwned + ?Sized,
+        L: Layout + LayoutNoDuplicates,
+        'env: 'txn,
+    {
+        let name = db.name_arc();
+        let key = K::encode(key).encode_context("compare_and_swap", name)?;
+        let expected = match expected {
+            Some(val) => Some(V::encode(val).encode_context("compare_and_swap", name)?),
+            None => None,
+        };
+
+        let current = self
+            .as_lmdb()
+            .get::<[u8], [u8]>(&db.0, &key)
+            .context("compare_and_swap", name)
+            .to_opt()?;
+        if current != expected.as_deref() {
+            return match current {
+                Some(val) => {
+                    let val = V::decode(val).decode_context("compare_and_swap", name)?;
+                    Ok(Err(Some(V::into_owned(val))))
+                }
+                None => Ok(Err(None)),
+            };
+        }
+
+        let existed = current.is_some();
+        match new {
+            Some(val) => {
+                let val = V::encode(val).encode_context("compare_and_swap", name)?;
+                self.as_lmdb_mut()
+                    .put(&db.0, &*key, &*val, put::Flags::empty())
+                    .context("compare_and_swap", name)?;
+            }
+            None if existed => self
+                .as_lmdb_mut()
+                .del_key(&db.0, &*key)
+                .context("compare_and_swap", name)?,
+            None => (),
+        }
+        Ok(Ok(()))
+    }
+
+    /// The dupsort counterpart of `compare_and_swap`: if the pair
+    /// `(key, expected)` is present it is replaced by `(key, new)`, or just
+    /// removed if `new` is `None`. Returns whether the pair was found.
+    ///
+    /// `cursor` is left positioned on the new pair, if any.
+    pub fn compare_and_swap_dup<K, V, L>(
+        &mut self,
+        cursor: &mut Cursor<K, V, L>,
+        key: &K::Item,
+        expected: &V::Item,
+        new: Option<&V::Item>,
+    ) -> Result<bool>
+    where
+        K: Codec + ?Sized,
+        V: Codec + ?Sized,
+        L: Layout + LayoutDupsort,
+    {
+        if cursor.seek_kv_opt(key, expected)?.is_none() {
+            return Ok(false);
+        }
+        cursor.del(self, del::Flags::empty())?;
+        if let Some(val) = new {
+            cursor.put(self, key, val, put::Flags::empty())?;
+        }
+        Ok(true)
     }
 
     #[inline]
@@ -148,3 +598,33 @@ impl<'txn> std::ops::Deref for WriteAccessor<'txn> {
         &self.0
     }
 }
+
+/// Integers that `fetch_add` can add to, failing instead of wrapping.
+pub trait CheckedAdd: Sized {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! checked_add {
+    ($($t:ty),*) => {$(
+        impl CheckedAdd for $t {
+            #[inline]
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+        }
+    )*};
+}
+
+checked_add!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+fn read_counter<V: LmdbRawIfUnaligned>(bytes: Option<&[u8]>) -> lmdb_zero::Result<Option<V>> {
+    bytes
+        .map(|bytes| Unaligned::<V>::from_lmdb_bytes(bytes).map(Unaligned::get))
+        .transpose()
+        .map_err(lmdb_zero::Error::ValRejected)
+}
+
+fn add_counter<V: CheckedAdd>(old: V, delta: V) -> Result<V> {
+    old.checked_add(delta)
+        .ok_or(Error::Overflow(Context::default()))
+}
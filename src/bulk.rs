@@ -0,0 +1,147 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+
+use lmdb_zero::traits::LmdbResultExt;
+use lmdb_zero::{db, error, put};
+
+use crate::error::{AsLmdbError, EncodeContext, Error, Result, WithContext};
+use crate::{Codec, Database, Layout, WriteTransaction};
+
+/// Loads sorted records into a database with `MDB_APPEND` (and
+/// `MDB_APPENDDUP` for further duplicates of a key in dupsort layouts), which
+/// skips the B-tree search a plain `put` does and fills pages completely.
+///
+/// Records are written in transactions of `chunk_size` records, each of which
+/// is committed before the next begins, so a failure part way through leaves
+/// the chunks before it in place.
+pub struct BulkLoader<'a, 'e, K, V, L>
+where
+    K: ?Sized,
+    V: ?Sized,
+    L: Layout,
+{
+    db: &'a Database<'e, K, V, L>,
+    chunk_size: usize,
+}
+
+impl<'a, 'e, K, V, L> BulkLoader<'a, 'e, K, V, L>
+where
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+    L: Layout,
+{
+    #[inline]
+    pub fn new(db: &'a Database<'e, K, V, L>) -> Self {
+        BulkLoader {
+            db,
+            chunk_size: 100_000,
+        }
+    }
+
+    /// Sets how many records are written per transaction.
+    #[inline]
+    pub fn chunk_size(mut self, records: usize) -> Self {
+        self.chunk_size = records.max(1);
+        self
+    }
+
+    /// Writes `items`, which must be in the database's order, returning the
+    /// number of records loaded.
+    ///
+    /// A record that does not sort after the previous one (or, for the first
+    /// record, after the existing contents) fails with `Error::OutOfOrder`.
+    /// With `LmdbLayoutDefault` this includes a repeated key.
+    pub fn load<I, KI, VI>(&self, items: I) -> Result<u64>
+    where
+        I: IntoIterator<Item = (KI, VI)>,
+        KI: Borrow<K::Item>,
+        VI: Borrow<V::Item>,
+    {
+        let name = self.db.name_arc();
+        self.load_with(items.into_iter().map(Ok), |(key, val), append| {
+            let key = K::encode(key.borrow()).encode_context("bulk load", name)?;
+            let val = V::encode(val.borrow()).encode_context("bulk load", name)?;
+            append(&key, &val)
+        })
+    }
+
+    /// Loads records that `encode` passes on to `append` as encoded keys and
+    /// values.
+    pub(crate) fn load_with<T, I, F>(&self, items: I, encode: F) -> Result<u64>
+    where
+        I: Iterator<Item = Result<T>>,
+        F: Fn(&T, &mut dyn FnMut(&[u8], &[u8]) -> Result<()>) -> Result<()>,
+    {
+        let name = self.db.name_arc();
+        let dupsort = L::flags().contains(db::DUPSORT);
+
+        let mut items = items.peekable();
+        let mut last: Option<(Vec<u8>, Vec<u8>)> = None;
+        let mut position = 0;
+        while items.peek().is_some() {
+            let txn = WriteTransaction::new(self.db.env())?;
+            {
+                let mut cursor = txn.cursor(self.db)?;
+                let mut access = txn.access();
+                if last.is_none() {
+                    last = cursor
+                        .0
+                        .last::<[u8], [u8]>(access.as_lmdb())
+                        .to_opt()
+                        .context("bulk load", name)?
+                        .map(|(key, val)| (key.to_vec(), val.to_vec()));
+                }
+                let mut append = |key: &[u8], val: &[u8]| -> Result<()> {
+                    // Check the order here, as LMDB only reports KEYEXIST.
+                    // APPEND also rejects a key equal to the last one, so
+                    // further duplicates of it go in with APPENDDUP alone.
+                    let mut flags = put::APPEND;
+                    if let Some((last_key, last_val)) = &last {
+                        let in_order = match cursor.cmp_keys(last_key, key) {
+                            Ordering::Less => true,
+                            Ordering::Equal => {
+                                flags = put::APPENDDUP;
+                                dupsort && cursor.cmp_values(last_val, val) == Ordering::Less
+                            }
+                            Ordering::Greater => false,
+                        };
+                        if !in_order {
+                            return Err(self.out_of_order(position, key));
+                        }
+                    }
+                    match cursor
+                        .0
+                        .put(access.as_lmdb_mut(), key, val, flags)
+                        .context("bulk load", name)
+                    {
+                        Err(err) if err.lmdb_code() == Some(error::KEYEXIST) => {
+                            return Err(self.out_of_order(position, key));
+                        }
+                        result => result?,
+                    }
+
+                    let (last_key, last_val) = last.get_or_insert_with(Default::default);
+                    last_key.clear();
+                    last_key.extend_from_slice(key);
+                    last_val.clear();
+                    last_val.extend_from_slice(val);
+                    position += 1;
+                    Ok(())
+                };
+                for item in items.by_ref().take(self.chunk_size) {
+                    encode(&item?, &mut append).context("bulk load", name)?;
+                }
+            }
+            txn.commit()?;
+        }
+        Ok(position)
+    }
+
+    fn out_of_order(&self, position: u64, key: &[u8]) -> Error {
+        Error::OutOfOrder {
+            db: self.db.name().map(str::to_owned),
+            position,
+            key: key.to_vec(),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::borrow::Cow;
+
+use lmdb_zero::traits::{AsLmdbBytes, FromLmdbBytes};
+use lmdb_zero::{Error, Result};
+
+/// Converts between the items stored in a `Database` and their on-disk bytes.
+///
+/// `Database<K, V, L>` is parameterised by codecs rather than storage types:
+/// `put` and friends take `&K::Item`/`&V::Item`, and reads return
+/// `K::Decoded<'access>`/`V::Decoded<'access>`, which may borrow from the
+/// transaction (zero-copy) or be an owned value.
+///
+/// Every type implementing lmdb-zero's `AsLmdbBytes + FromLmdbBytes` (`str`,
+/// `[u8]`, `LmdbRaw` types, ...) is its own codec and decodes to a reference.
+///
+/// Codecs reject malformed input with `lmdb_zero::Error::ValRejected`, which
+/// accessors and cursors report as `Error::Encode` or `Error::Decode`.
+pub trait Codec {
+    type Item: ?Sized;
+    type Decoded<'a>
+    where
+        Self: 'a;
+
+    fn encode(item: &Self::Item) -> Result<Cow<'_, [u8]>>;
+    fn decode(bytes: &[u8]) -> Result<Self::Decoded<'_>>;
+}
+
+impl<T> Codec for T
+where
+    T: AsLmdbBytes + FromLmdbBytes + ?Sized,
+{
+    type Item = T;
+    type Decoded<'a>
+        = &'a T
+    where
+        T: 'a;
+
+    #[inline]
+    fn encode(item: &T) -> Result<Cow<'_, [u8]>> {
+        Ok(Cow::Borrowed(item.as_lmdb_bytes()))
+    }
+
+    #[inline]
+    fn decode(bytes: &[u8]) -> Result<&T> {
+        T::from_lmdb_bytes(bytes).map_err(Error::ValRejected)
+    }
+}
+
+/// Codecs whose decoded values can be detached from the transaction they
+/// were read in, e.g. to return them from a `TypedEnv::read` closure.
+pub trait CodecOwned: Codec {
+    type Owned;
+
+    fn into_owned(decoded: Self::Decoded<'_>) -> Self::Owned;
+}
+
+impl<T> CodecOwned for T
+where
+    T: AsLmdbBytes + FromLmdbBytes + ToOwned + ?Sized,
+{
+    type Owned = T::Owned;
+
+    #[inline]
+    fn into_owned(decoded: &T) -> T::Owned {
+        decoded.to_owned()
+    }
+}
+
+/// Codecs whose encoded keys can be scanned by a prefix of type `P`; see
+/// `Cursor::prefix`. The encoding of `prefix` must be a bytewise prefix of the
+/// encoding of every key it matches.
+pub trait Prefix<P: ?Sized>: Codec {
+    fn encode_prefix(prefix: &P) -> Result<Cow<'_, [u8]>>;
+}
+
+impl Prefix<str> for str {
+    #[inline]
+    fn encode_prefix(prefix: &str) -> Result<Cow<'_, [u8]>> {
+        Ok(Cow::Borrowed(prefix.as_bytes()))
+    }
+}
+
+impl Prefix<[u8]> for [u8] {
+    #[inline]
+    fn encode_prefix(prefix: &[u8]) -> Result<Cow<'_, [u8]>> {
+        Ok(Cow::Borrowed(prefix))
+    }
+}
@@ -0,0 +1,82 @@
+use std::cmp::Ordering;
+
+use lmdb_zero::db;
+
+/// An encoded key and value.
+pub(crate) type Record = (Vec<u8>, Vec<u8>);
+
+/// The order LMDB keeps records in, following the database's
+/// `INTEGERKEY`/`REVERSEKEY` and `INTEGERDUP`/`REVERSEDUP` flags, so that
+/// encoded keys and values can be compared without a transaction.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct RecordOrder {
+    keys: ByteOrder,
+    values: Option<ByteOrder>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+enum ByteOrder {
+    #[default]
+    Lexical,
+    Reverse,
+    Integer,
+}
+
+impl RecordOrder {
+    pub(crate) fn new(flags: db::Flags) -> Self {
+        let byte_order = |integer, reverse| {
+            if flags.contains(integer) {
+                ByteOrder::Integer
+            } else if flags.contains(reverse) {
+                ByteOrder::Reverse
+            } else {
+                ByteOrder::Lexical
+            }
+        };
+        RecordOrder {
+            keys: byte_order(db::INTEGERKEY, db::REVERSEKEY),
+            values: if flags.contains(db::DUPSORT) {
+                Some(byte_order(db::INTEGERDUP, db::REVERSEDUP))
+            } else {
+                None
+            },
+        }
+    }
+
+    #[inline]
+    pub(crate) fn cmp_keys(&self, a: &[u8], b: &[u8]) -> Ordering {
+        self.keys.cmp(a, b)
+    }
+
+    /// Compares values as duplicates of one key. Without `DUPSORT` values
+    /// are not ordered, and compare bytewise.
+    #[inline]
+    pub(crate) fn cmp_values(&self, a: &[u8], b: &[u8]) -> Ordering {
+        self.values.unwrap_or_default().cmp(a, b)
+    }
+
+    /// Orders by key and, for dupsort databases, then by value. Records the
+    /// database cannot both hold compare equal.
+    pub(crate) fn cmp(&self, a: &Record, b: &Record) -> Ordering {
+        let by_key = self.keys.cmp(&a.0, &b.0);
+        match self.values {
+            Some(values) => by_key.then_with(|| values.cmp(&a.1, &b.1)),
+            None => by_key,
+        }
+    }
+}
+
+impl ByteOrder {
+    fn cmp(self, a: &[u8], b: &[u8]) -> Ordering {
+        match self {
+            ByteOrder::Lexical => a.cmp(b),
+            ByteOrder::Reverse => a.iter().rev().cmp(b.iter().rev()),
+            // Native-endian unsigned integers, all of the same size.
+            ByteOrder::Integer if cfg!(target_endian = "little") => a
+                .len()
+                .cmp(&b.len())
+                .then_with(|| a.iter().rev().cmp(b.iter().rev())),
+            ByteOrder::Integer => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+        }
+    }
+}
@@ -1,10 +1,17 @@
+use std::cmp::Ordering;
+use std::ops::RangeBounds;
+use std::sync::Arc;
+
 use lmdb_zero::{
-    traits::{AsLmdbBytes, FromLmdbBytes, FromReservedLmdbBytes, LmdbRaw},
-    Result,
+    traits::{FromLmdbBytes, FromReservedLmdbBytes, LmdbRaw},
+    MaybeOwned,
 };
 
+use crate::compare::RecordOrder;
+use crate::error::{DecodeContext, EncodeContext, Result, ResultExt, WithContext};
 use crate::{
-    ConstAccessor, Layout, LayoutDupfixed, LayoutDupsort, LayoutNoDuplicates, WriteAccessor,
+    Codec, ConstAccessor, DupIter, GroupIter, Layout, LayoutDupfixed, LayoutDupsort,
+    LayoutNoDuplicates, PageIter, Prefix, PrefixIter, RangeIter, WriteAccessor,
 };
 
 pub struct Cursor<'t, 'd, K: ?Sized, V: ?Sized, L: Layout>(
@@ -12,6 +19,8 @@ pub struct Cursor<'t, 'd, K: ?Sized, V: ?Sized, L: Layout>(
     std::marker::PhantomData<K>,
     std::marker::PhantomData<V>,
     std::marker::PhantomData<L>,
+    pub(crate) Option<Arc<str>>,
+    pub(crate) RecordOrder,
 );
 
 #[derive(Debug)]
@@ -20,14 +29,43 @@ pub struct StaleCursor<'d, K: ?Sized, V: ?Sized, L: Layout>(
     pub(crate) std::marker::PhantomData<K>,
     pub(crate) std::marker::PhantomData<V>,
     pub(crate) std::marker::PhantomData<L>,
+    pub(crate) Option<Arc<str>>,
+    pub(crate) RecordOrder,
 );
 
-macro_rules! t_get_0_kv {
+/// Documents the `*_opt` variant of `$method`.
+macro_rules! opt_doc {
     ($method:ident) => {
+        concat!(
+            "Like `",
+            stringify!($method),
+            "`, but returns `Ok(None)` instead of `Error::NotFound`."
+        )
+    };
+}
+
+macro_rules! t_get_0_kv {
+    ($method:ident, $opt:ident) => {
         fn $method<'access>(
             &mut self,
             access: &'access ConstAccessor<'t>,
-        ) -> Result<(&'access K, &'access V)>;
+        ) -> Result<(K::Decoded<'access>, V::Decoded<'access>)>
+        where
+            K: 'access,
+            V: 'access;
+
+        #[doc = opt_doc!($method)]
+        #[inline]
+        fn $opt<'access>(
+            &mut self,
+            access: &'access ConstAccessor<'t>,
+        ) -> Result<Option<(K::Decoded<'access>, V::Decoded<'access>)>>
+        where
+            K: 'access,
+            V: 'access,
+        {
+            self.$method(access).to_opt()
+        }
     };
 }
 
@@ -37,23 +75,63 @@ macro_rules! c_get_0_kv {
         fn $method<'access>(
             &mut self,
             access: &'access ConstAccessor<'t>,
-        ) -> Result<(&'access K, &'access V)> {
-            self.0.$method(&access.as_lmdb())
+        ) -> Result<(K::Decoded<'access>, V::Decoded<'access>)>
+        where
+            K: 'access,
+            V: 'access,
+        {
+            let op = stringify!($method);
+            let (key, val) = self
+                .0
+                .$method::<[u8], [u8]>(access.as_lmdb())
+                .context(op, self.db_name())?;
+            Ok((
+                K::decode(key).decode_context(op, self.db_name())?,
+                V::decode(val).decode_context(op, self.db_name())?,
+            ))
         }
     };
 }
 
 macro_rules! t_get_0_v {
-    ($method:ident) => {
-        fn $method<'access>(&mut self, access: &'access ConstAccessor<'t>) -> Result<&'access V>;
+    ($method:ident, $opt:ident) => {
+        fn $method<'access>(
+            &mut self,
+            access: &'access ConstAccessor<'t>,
+        ) -> Result<V::Decoded<'access>>
+        where
+            V: 'access;
+
+        #[doc = opt_doc!($method)]
+        #[inline]
+        fn $opt<'access>(
+            &mut self,
+            access: &'access ConstAccessor<'t>,
+        ) -> Result<Option<V::Decoded<'access>>>
+        where
+            V: 'access,
+        {
+            self.$method(access).to_opt()
+        }
     };
 }
 
 macro_rules! c_get_0_v {
     ($method:ident) => {
         #[inline]
-        fn $method<'access>(&mut self, access: &'access ConstAccessor<'t>) -> Result<&'access V> {
-            self.0.$method(&access.as_lmdb())
+        fn $method<'access>(
+            &mut self,
+            access: &'access ConstAccessor<'t>,
+        ) -> Result<V::Decoded<'access>>
+        where
+            V: 'access,
+        {
+            let op = stringify!($method);
+            let val = self
+                .0
+                .$method::<[u8]>(access.as_lmdb())
+                .context(op, self.db_name())?;
+            V::decode(val).decode_context(op, self.db_name())
         }
     };
 }
@@ -63,7 +141,7 @@ macro_rules! t_change_in_place {
         fn $method<'access>(
             &mut self,
             access: &'access mut WriteAccessor,
-            key: &K,
+            key: &K::Item,
             flags: lmdb_zero::put::Flags,
         ) -> Result<&'access mut V>;
     };
@@ -75,20 +153,27 @@ macro_rules! c_change_in_place {
         fn $method<'access>(
             &mut self,
             access: &'access mut WriteAccessor,
-            key: &K,
+            key: &K::Item,
             flags: lmdb_zero::put::Flags,
         ) -> Result<&'access mut V> {
-            self.0.$method(access.as_lmdb_mut(), key, flags)
+            let op = stringify!($method);
+            let key = K::encode(key).encode_context(op, self.db_name())?;
+            self.0
+                .$method(access.as_lmdb_mut(), &*key, flags)
+                .context(op, self.db_name())
         }
     };
 }
 
 macro_rules! t_change_in_place_unsized {
     ($method:ident) => {
+        /// # Safety
+        ///
+        /// See the `lmdb_zero::Cursor` method of the same name.
         unsafe fn $method<'access>(
             &mut self,
             access: &'access mut WriteAccessor,
-            key: &K,
+            key: &K::Item,
             size: usize,
             flags: lmdb_zero::put::Flags,
         ) -> Result<&'access mut V>;
@@ -101,11 +186,15 @@ macro_rules! c_change_in_place_unsized {
         unsafe fn $method<'access>(
             &mut self,
             access: &'access mut WriteAccessor,
-            key: &K,
+            key: &K::Item,
             size: usize,
             flags: lmdb_zero::put::Flags,
         ) -> Result<&'access mut V> {
-            self.0.$method(access.as_lmdb_mut(), key, size, flags)
+            let op = stringify!($method);
+            let key = K::encode(key).encode_context(op, self.db_name())?;
+            self.0
+                .$method(access.as_lmdb_mut(), &*key, size, flags)
+                .context(op, self.db_name())
         }
     };
 }
@@ -115,7 +204,7 @@ macro_rules! t_put {
         fn $method(
             &mut self,
             access: &mut WriteAccessor,
-            key: &K,
+            key: &K::Item,
             value: &$value_type,
             flags: lmdb_zero::put::Flags,
         ) -> Result<$result_type>;
@@ -128,22 +217,60 @@ macro_rules! c_put {
         fn $method(
             &mut self,
             access: &mut WriteAccessor,
-            key: &K,
+            key: &K::Item,
             value: &$value_type,
             flags: lmdb_zero::put::Flags,
         ) -> Result<$result_type> {
-            self.0.$method(access.as_lmdb_mut(), key, value, flags)
+            let op = stringify!($method);
+            let key = K::encode(key).encode_context(op, self.db_name())?;
+            self.0
+                .$method(access.as_lmdb_mut(), &*key, value, flags)
+                .context(op, self.db_name())
+        }
+    };
+    ($method:ident, $value_type:ty, $result_type:ty, encode) => {
+        #[inline]
+        fn $method(
+            &mut self,
+            access: &mut WriteAccessor,
+            key: &K::Item,
+            value: &$value_type,
+            flags: lmdb_zero::put::Flags,
+        ) -> Result<$result_type> {
+            let op = stringify!($method);
+            let key = K::encode(key).encode_context(op, self.db_name())?;
+            let value = V::encode(value).encode_context(op, self.db_name())?;
+            self.0
+                .$method(access.as_lmdb_mut(), &*key, &*value, flags)
+                .context(op, self.db_name())
         }
     };
 }
 
 macro_rules! t_seek_both {
-    ($method:ident) => {
+    ($method:ident, $opt:ident) => {
         fn $method<'access>(
             &mut self,
             access: &'access ConstAccessor<'t>,
-            key: &K,
-        ) -> Result<(&'access K, &'access V)>;
+            key: &K::Item,
+        ) -> Result<(K::Decoded<'access>, V::Decoded<'access>)>
+        where
+            K: 'access,
+            V: 'access;
+
+        #[doc = opt_doc!($method)]
+        #[inline]
+        fn $opt<'access>(
+            &mut self,
+            access: &'access ConstAccessor<'t>,
+            key: &K::Item,
+        ) -> Result<Option<(K::Decoded<'access>, V::Decoded<'access>)>>
+        where
+            K: 'access,
+            V: 'access,
+        {
+            self.$method(access, key).to_opt()
+        }
     };
 }
 
@@ -153,9 +280,22 @@ macro_rules! c_seek_both {
         fn $method<'access>(
             &mut self,
             access: &'access ConstAccessor<'t>,
-            key: &K,
-        ) -> Result<(&'access K, &'access V)> {
-            self.0.$method(&access.as_lmdb(), key)
+            key: &K::Item,
+        ) -> Result<(K::Decoded<'access>, V::Decoded<'access>)>
+        where
+            K: 'access,
+            V: 'access,
+        {
+            let op = stringify!($method);
+            let key = K::encode(key).encode_context(op, self.db_name())?;
+            let (key, val) = self
+                .0
+                .$method::<[u8], [u8]>(access.as_lmdb(), &key)
+                .context(op, self.db_name())?;
+            Ok((
+                K::decode(key).decode_context(op, self.db_name())?,
+                V::decode(val).decode_context(op, self.db_name())?,
+            ))
         }
     };
 }
@@ -173,12 +313,27 @@ where
             std::marker::PhantomData,
             std::marker::PhantomData,
             std::marker::PhantomData,
+            None,
+            RecordOrder::default(),
         )
     }
 
+    #[inline]
+    pub(crate) fn with_db_name(mut self, name: Option<&Arc<str>>) -> Self {
+        self.4 = name.cloned();
+        self
+    }
+
+    #[inline]
+    pub(crate) fn db_name(&self) -> Option<&Arc<str>> {
+        self.4.as_ref()
+    }
+
     #[inline]
     pub fn del(&mut self, access: &mut WriteAccessor, flags: lmdb_zero::del::Flags) -> Result<()> {
-        self.0.del(access.as_lmdb_mut(), flags)
+        self.0
+            .del(access.as_lmdb_mut(), flags)
+            .context("del", self.db_name())
     }
 
     #[inline]
@@ -195,6 +350,64 @@ where
     pub fn into_lmdb(self) -> lmdb_zero::Cursor<'t, 'd> {
         self.0
     }
+
+    /// Sets the order of the cursor's database, which `from_lmdb` cannot
+    /// know and takes to be bytewise.
+    #[inline]
+    pub(crate) fn with_order(mut self, order: RecordOrder) -> Self {
+        self.5 = order;
+        self
+    }
+
+    /// Compares two encoded keys in the order of the database.
+    #[inline]
+    pub(crate) fn cmp_keys(&self, a: &[u8], b: &[u8]) -> Ordering {
+        self.5.cmp_keys(a, b)
+    }
+
+    /// Compares two encoded values in the order of the database's
+    /// duplicates.
+    #[inline]
+    pub(crate) fn cmp_values(&self, a: &[u8], b: &[u8]) -> Ordering {
+        self.5.cmp_values(a, b)
+    }
+}
+
+impl<'t, 'd, K, V, L> Cursor<'t, 'd, K, V, L>
+where
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+    L: Layout,
+{
+    /// Iterates over the entries whose keys fall within `range`, in database
+    /// order. For dupsort databases every value of each key is yielded.
+    #[inline]
+    pub fn range<'a, 'access, R>(
+        &'a mut self,
+        access: &'access ConstAccessor<'t>,
+        range: R,
+    ) -> Result<RangeIter<'a, 'access, 't, 'd, K, V, L>>
+    where
+        R: RangeBounds<K::Item>,
+    {
+        RangeIter::new(MaybeOwned::Borrowed(self), access, range)
+    }
+
+    /// Iterates over the entries whose keys start with `prefix`. Matching is
+    /// bytewise on the encoded key, so it does not depend on computing an
+    /// upper bound (which does not exist for a prefix of all `0xff` bytes).
+    #[inline]
+    pub fn prefix<'a, 'access, P>(
+        &'a mut self,
+        access: &'access ConstAccessor<'t>,
+        prefix: &P,
+    ) -> Result<PrefixIter<'a, 'access, 't, 'd, K, V, L>>
+    where
+        K: Prefix<P>,
+        P: ?Sized,
+    {
+        PrefixIter::new(MaybeOwned::Borrowed(self), access, prefix)
+    }
 }
 
 impl<'d, K, V, L: Layout> StaleCursor<'d, K, V, L> {
@@ -205,9 +418,23 @@ impl<'d, K, V, L: Layout> StaleCursor<'d, K, V, L> {
             std::marker::PhantomData,
             std::marker::PhantomData,
             std::marker::PhantomData,
+            None,
+            RecordOrder::default(),
         )
     }
 
+    #[inline]
+    pub(crate) fn with_db_name(mut self, name: Option<&Arc<str>>) -> Self {
+        self.4 = name.cloned();
+        self
+    }
+
+    #[inline]
+    pub(crate) fn with_order(mut self, order: RecordOrder) -> Self {
+        self.5 = order;
+        self
+    }
+
     #[inline]
     pub fn as_lmdb(&self) -> &lmdb_zero::StaleCursor<'d> {
         &self.0
@@ -237,29 +464,29 @@ where
 {
     #[inline]
     fn count(&mut self) -> Result<usize> {
-        self.0.count()
+        self.0.count().context("count", self.db_name())
     }
 }
 
 pub trait CursorFromXFrom<'t, 'd, K, V, L>
 where
-    K: FromLmdbBytes + ?Sized,
-    V: FromLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout,
 {
-    t_get_0_kv!(first);
-    t_get_0_kv!(get_current);
-    t_get_0_kv!(last);
-    t_get_0_kv!(next);
-    t_get_0_kv!(next_nodup);
-    t_get_0_kv!(prev);
-    t_get_0_kv!(prev_nodup);
+    t_get_0_kv!(first, first_opt);
+    t_get_0_kv!(get_current, get_current_opt);
+    t_get_0_kv!(last, last_opt);
+    t_get_0_kv!(next, next_opt);
+    t_get_0_kv!(next_nodup, next_nodup_opt);
+    t_get_0_kv!(prev, prev_opt);
+    t_get_0_kv!(prev_nodup, prev_nodup_opt);
 }
 
 impl<'t, 'd, K, V, L> CursorFromXFrom<'t, 'd, K, V, L> for Cursor<'t, 'd, K, V, L>
 where
-    K: FromLmdbBytes + ?Sized,
-    V: FromLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout,
 {
     c_get_0_kv!(first);
@@ -271,26 +498,70 @@ where
     c_get_0_kv!(prev_nodup);
 }
 
+impl<'t, 'd, K, V, L> Cursor<'t, 'd, K, V, L>
+where
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+    L: Layout + LayoutDupsort,
+{
+    /// Iterates over every value stored under `key`.
+    #[inline]
+    pub fn dups<'a, 'access>(
+        &'a mut self,
+        access: &'access ConstAccessor<'t>,
+        key: &K::Item,
+    ) -> Result<DupIter<'a, 'access, 't, 'd, K, V, L>> {
+        DupIter::new(MaybeOwned::Borrowed(self), access, key)
+    }
+
+    /// Iterates over each distinct key together with its values.
+    #[inline]
+    pub fn groups<'a, 'access>(
+        &'a mut self,
+        access: &'access ConstAccessor<'t>,
+    ) -> GroupIter<'a, 'access, 't, 'd, K, V, L> {
+        GroupIter::new(MaybeOwned::Borrowed(self), access)
+    }
+}
+
 pub trait CursorFromXFromDupsort<'t, 'd, K, V, L>
 where
-    K: FromLmdbBytes + ?Sized,
-    V: FromLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout + LayoutDupsort,
 {
-    t_get_0_kv!(next_dup);
-    t_get_0_kv!(prev_dup);
+    t_get_0_kv!(next_dup, next_dup_opt);
+    t_get_0_kv!(prev_dup, prev_dup_opt);
 }
 
 impl<'t, 'd, K, V, L> CursorFromXFromDupsort<'t, 'd, K, V, L> for Cursor<'t, 'd, K, V, L>
 where
-    K: FromLmdbBytes + ?Sized,
-    V: FromLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout + LayoutDupsort,
 {
     c_get_0_kv!(next_dup);
     c_get_0_kv!(prev_dup);
 }
 
+impl<'t, 'd, K, V, L> Cursor<'t, 'd, K, V, L>
+where
+    K: Codec + ?Sized,
+    L: Layout + LayoutDupsort + LayoutDupfixed,
+    [V]: FromLmdbBytes,
+{
+    /// Iterates over the values stored under `key` a page at a time; use
+    /// `PageIter::values` to visit them individually.
+    #[inline]
+    pub fn pages<'a, 'access>(
+        &'a mut self,
+        access: &'access ConstAccessor<'t>,
+        key: &K::Item,
+    ) -> Result<PageIter<'a, 'access, 't, 'd, K, V, L>> {
+        PageIter::new(MaybeOwned::Borrowed(self), access, key)
+    }
+}
+
 pub trait CursorXFromDupfixed<'t, 'd, K, V, L>
 where
     L: Layout + LayoutDupsort + LayoutDupfixed,
@@ -303,6 +574,26 @@ where
 
     fn get_multiple<'access>(&mut self, access: &'access ConstAccessor<'t>)
         -> Result<&'access [V]>;
+
+    /// Like `next_multiple`, but returns `Ok(None)` instead of
+    /// `Error::NotFound`.
+    #[inline]
+    fn next_multiple_opt<'access>(
+        &mut self,
+        access: &'access ConstAccessor<'t>,
+    ) -> Result<Option<&'access [V]>> {
+        self.next_multiple(access).to_opt()
+    }
+
+    /// Like `get_multiple`, but returns `Ok(None)` instead of
+    /// `Error::NotFound`.
+    #[inline]
+    fn get_multiple_opt<'access>(
+        &mut self,
+        access: &'access ConstAccessor<'t>,
+    ) -> Result<Option<&'access [V]>> {
+        self.get_multiple(access).to_opt()
+    }
 }
 
 impl<'t, 'd, K, V, L> CursorXFromDupfixed<'t, 'd, K, V, L> for Cursor<'t, 'd, K, V, L>
@@ -315,7 +606,9 @@ where
         &mut self,
         access: &'access ConstAccessor<'t>,
     ) -> Result<&'access [V]> {
-        self.0.get_multiple::<[V]>(&access.as_lmdb())
+        self.0
+            .get_multiple::<[V]>(access.as_lmdb())
+            .decode_context("get_multiple", self.db_name())
     }
 
     #[inline]
@@ -323,22 +616,24 @@ where
         &mut self,
         access: &'access ConstAccessor<'t>,
     ) -> Result<&'access [V]> {
-        self.0.next_multiple::<[V]>(&access.as_lmdb())
+        self.0
+            .next_multiple::<[V]>(access.as_lmdb())
+            .decode_context("next_multiple", self.db_name())
     }
 }
 
 pub trait CursorXFromDupsort<'t, 'd, K, V, L>
 where
-    V: FromLmdbBytes + ?Sized,
+    V: Codec + ?Sized,
     L: LayoutDupsort + Layout,
 {
-    t_get_0_v!(first_dup);
-    t_get_0_v!(last_dup);
+    t_get_0_v!(first_dup, first_dup_opt);
+    t_get_0_v!(last_dup, last_dup_opt);
 }
 
 impl<'t, 'd, K, V, L> CursorXFromDupsort<'t, 'd, K, V, L> for Cursor<'t, 'd, K, V, L>
 where
-    V: FromLmdbBytes + ?Sized,
+    V: Codec + ?Sized,
     L: LayoutDupsort + Layout,
 {
     c_get_0_v!(first_dup);
@@ -347,119 +642,179 @@ where
 
 pub trait CursorAsXAs<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
-    V: AsLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout,
 {
-    t_put!(put, V, ());
-    t_put!(overwrite, V, ());
+    t_put!(put, V::Item, ());
+    t_put!(overwrite, V::Item, ());
 }
 
 impl<'t, 'd, K, V, L> CursorAsXAs<'t, 'd, K, V, L> for Cursor<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
-    V: AsLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout,
 {
-    c_put!(put, V, ());
-    c_put!(overwrite, V, ());
+    c_put!(put, V::Item, (), encode);
+    c_put!(overwrite, V::Item, (), encode);
 }
 
 pub trait CursorAsXAsDupsort<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
-    V: AsLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout + LayoutDupsort,
 {
-    fn seek_kv(&mut self, key: &K, val: &V) -> Result<()>;
+    fn seek_kv(&mut self, key: &K::Item, val: &V::Item) -> Result<()>;
+
+    /// Like `seek_kv`, but returns `Ok(None)` instead of `Error::NotFound`.
+    #[inline]
+    fn seek_kv_opt(&mut self, key: &K::Item, val: &V::Item) -> Result<Option<()>> {
+        self.seek_kv(key, val).to_opt()
+    }
 }
 
 impl<'t, 'd, K, V, L> CursorAsXAsDupsort<'t, 'd, K, V, L> for Cursor<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
-    V: AsLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout + LayoutDupsort,
 {
     #[inline]
-    fn seek_kv(&mut self, key: &K, val: &V) -> Result<()> {
-        self.0.seek_kv(key, val)
+    fn seek_kv(&mut self, key: &K::Item, val: &V::Item) -> Result<()> {
+        let key = K::encode(key).encode_context("seek_kv", self.db_name())?;
+        let val = V::encode(val).encode_context("seek_kv", self.db_name())?;
+        self.0
+            .seek_kv(&*key, &*val)
+            .context("seek_kv", self.db_name())
     }
 }
 
 pub trait CursorAsXAsFromDupsort<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
-    V: AsLmdbBytes + FromLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout + LayoutDupsort,
 {
     fn seek_k_nearest_v<'access>(
         &mut self,
         access: &'access ConstAccessor<'t>,
-        key: &K,
-        val: &V,
-    ) -> Result<&'access V>;
+        key: &K::Item,
+        val: &V::Item,
+    ) -> Result<V::Decoded<'access>>
+    where
+        V: 'access;
+
+    /// Like `seek_k_nearest_v`, but returns `Ok(None)` instead of
+    /// `Error::NotFound`.
+    #[inline]
+    fn seek_k_nearest_v_opt<'access>(
+        &mut self,
+        access: &'access ConstAccessor<'t>,
+        key: &K::Item,
+        val: &V::Item,
+    ) -> Result<Option<V::Decoded<'access>>>
+    where
+        V: 'access,
+    {
+        self.seek_k_nearest_v(access, key, val).to_opt()
+    }
 }
 
 impl<'t, 'd, K, V, L> CursorAsXAsFromDupsort<'t, 'd, K, V, L> for Cursor<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
-    V: AsLmdbBytes + FromLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout + LayoutDupsort,
 {
     #[inline]
     fn seek_k_nearest_v<'access>(
         &mut self,
         access: &'access ConstAccessor<'t>,
-        key: &K,
-        val: &V,
-    ) -> Result<&'access V> {
-        self.0.seek_k_nearest_v(&access.as_lmdb(), key, val)
+        key: &K::Item,
+        val: &V::Item,
+    ) -> Result<V::Decoded<'access>>
+    where
+        V: 'access,
+    {
+        let op = "seek_k_nearest_v";
+        let key = K::encode(key).encode_context(op, self.db_name())?;
+        let val = V::encode(val).encode_context(op, self.db_name())?;
+        let val = self
+            .0
+            .seek_k_nearest_v::<[u8], [u8]>(access.as_lmdb(), &key, &val)
+            .context(op, self.db_name())?;
+        V::decode(val).decode_context(op, self.db_name())
     }
 }
 
 pub trait CursorAsXFrom<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
-    V: FromLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout,
 {
     fn seek_k<'access>(
         &mut self,
         access: &'access ConstAccessor<'t>,
-        key: &K,
-    ) -> Result<&'access V>;
+        key: &K::Item,
+    ) -> Result<V::Decoded<'access>>
+    where
+        V: 'access;
+
+    /// Like `seek_k`, but returns `Ok(None)` instead of `Error::NotFound`.
+    #[inline]
+    fn seek_k_opt<'access>(
+        &mut self,
+        access: &'access ConstAccessor<'t>,
+        key: &K::Item,
+    ) -> Result<Option<V::Decoded<'access>>>
+    where
+        V: 'access,
+    {
+        self.seek_k(access, key).to_opt()
+    }
 }
 
 impl<'t, 'd, K, V, L> CursorAsXFrom<'t, 'd, K, V, L> for Cursor<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
-    V: FromLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout,
 {
     #[inline]
     fn seek_k<'access>(
         &mut self,
         access: &'access ConstAccessor<'t>,
-        key: &K,
-    ) -> Result<&'access V> {
-        self.0.seek_k(&access.as_lmdb(), key)
+        key: &K::Item,
+    ) -> Result<V::Decoded<'access>>
+    where
+        V: 'access,
+    {
+        let key = K::encode(key).encode_context("seek_k", self.db_name())?;
+        let val = self
+            .0
+            .seek_k::<[u8], [u8]>(access.as_lmdb(), &key)
+            .context("seek_k", self.db_name())?;
+        V::decode(val).decode_context("seek_k", self.db_name())
     }
 }
 
 pub trait CursorAsFromXFrom<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + FromLmdbBytes + ?Sized,
-    V: FromLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout,
 {
-    t_seek_both!(seek_k_both);
-    t_seek_both!(seek_range_k);
+    t_seek_both!(seek_k_both, seek_k_both_opt);
+    t_seek_both!(seek_range_k, seek_range_k_opt);
 }
 
 impl<'t, 'd, K, V, L> CursorAsFromXFrom<'t, 'd, K, V, L> for Cursor<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + FromLmdbBytes + ?Sized,
-    V: FromLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
     L: Layout,
 {
     c_seek_both!(seek_k_both);
@@ -468,7 +823,7 @@ where
 
 pub trait CursorAsXFromReservedSizedNonDupsort<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
     V: FromReservedLmdbBytes + Sized,
     L: Layout + LayoutNoDuplicates,
 {
@@ -479,7 +834,7 @@ where
 impl<'t, 'd, K, V, L> CursorAsXFromReservedSizedNonDupsort<'t, 'd, K, V, L>
     for Cursor<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
     V: FromReservedLmdbBytes + Sized,
     L: Layout + LayoutNoDuplicates,
 {
@@ -489,7 +844,7 @@ where
 
 pub trait CursorAsXRawDupfixed<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
     V: LmdbRaw,
     L: Layout + LayoutDupsort + LayoutDupfixed,
 {
@@ -498,7 +853,7 @@ where
 
 impl<'t, 'd, K, V, L> CursorAsXRawDupfixed<'t, 'd, K, V, L> for Cursor<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
     V: LmdbRaw,
     L: Layout + LayoutDupsort + LayoutDupfixed,
 {
@@ -507,7 +862,7 @@ where
 
 pub trait CursorAsXFromReservedNonDupsort<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
     V: FromReservedLmdbBytes + ?Sized,
     L: Layout + LayoutNoDuplicates,
 {
@@ -517,7 +872,7 @@ where
 
 impl<'t, 'd, K, V, L> CursorAsXFromReservedNonDupsort<'t, 'd, K, V, L> for Cursor<'t, 'd, K, V, L>
 where
-    K: AsLmdbBytes + ?Sized,
+    K: Codec + ?Sized,
     V: FromReservedLmdbBytes + ?Sized,
     L: Layout + LayoutNoDuplicates,
 {
@@ -1,7 +1,17 @@
-use lmdb_zero::traits::LmdbResultExt;
-use lmdb_zero::{MaybeOwned, Result};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::ptr;
+use std::rc::Rc;
 
-use crate::{ConstAccessor, Cursor, Layout};
+use lmdb_zero::traits::FromLmdbBytes;
+use lmdb_zero::{error, MaybeOwned};
+
+use crate::error::{DecodeContext, EncodeContext, Error, Result, ResultExt, WithContext};
+use crate::{
+    Codec, CodecOwned, ConstAccessor, Cursor, Layout, LayoutDupfixed, LayoutDupsort, Prefix,
+};
 
 pub struct CursorIter<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, T, L>
 where
@@ -33,10 +43,10 @@ where
     ) -> Result<Self> {
         let head_val = head(&mut *cursor, access).to_opt()?;
         Ok(CursorIter {
-            cursor: cursor,
-            access: access,
+            cursor,
+            access,
             head: head_val,
-            next: next,
+            next,
         })
     }
 }
@@ -57,7 +67,856 @@ where
             match (self.next)(&mut *self.cursor, self.access).to_opt() {
                 Ok(Some(v)) => Some(Ok(v)),
                 Ok(None) => None,
-                Err(err) => Some(Err(err.into())),
+                Err(err) => Some(Err(err)),
+            }
+        }
+    }
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L>
+    CursorIter<'a, 'access, 'txn, 'db, K, V, (K::Decoded<'access>, V::Decoded<'access>), L>
+where
+    L: Layout,
+    K: CodecOwned + ?Sized + 'access,
+    V: CodecOwned + ?Sized + 'access,
+{
+    /// Yields owned copies of the entries, which outlive the transaction.
+    #[inline]
+    pub fn owned(self) -> OwnedIter<'access, Self, K, V> {
+        OwnedIter::new(self)
+    }
+}
+
+/// Adapts an iterator over decoded entries into one over `(K::Owned,
+/// V::Owned)` pairs that can be returned from a transaction.
+pub struct OwnedIter<'access, I, K: ?Sized, V: ?Sized> {
+    iter: I,
+    marker: PhantomData<fn(&'access K, &'access V)>,
+}
+
+impl<'access, I, K: ?Sized, V: ?Sized> OwnedIter<'access, I, K, V> {
+    #[inline]
+    pub fn new(iter: I) -> Self {
+        OwnedIter {
+            iter,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'access, I, K, V> Iterator for OwnedIter<'access, I, K, V>
+where
+    I: Iterator<Item = Result<(K::Decoded<'access>, V::Decoded<'access>)>>,
+    K: CodecOwned + ?Sized + 'access,
+    V: CodecOwned + ?Sized + 'access,
+{
+    type Item = Result<(K::Owned, V::Owned)>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let kv = self.iter.next()?;
+        Some(kv.map(|(key, val)| (K::into_owned(key), V::into_owned(val))))
+    }
+}
+
+impl<'access, I, K, V> DoubleEndedIterator for OwnedIter<'access, I, K, V>
+where
+    I: DoubleEndedIterator<Item = Result<(K::Decoded<'access>, V::Decoded<'access>)>>,
+    K: CodecOwned + ?Sized + 'access,
+    V: CodecOwned + ?Sized + 'access,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let kv = self.iter.next_back()?;
+        Some(kv.map(|(key, val)| (K::into_owned(key), V::into_owned(val))))
+    }
+}
+
+type RawItem<'a> = (&'a [u8], &'a [u8]);
+
+fn encode_bound<K: Codec + ?Sized>(bound: Bound<&K::Item>) -> Result<Bound<Vec<u8>>> {
+    Ok(match bound {
+        Bound::Included(key) => Bound::Included(encode::<K>(key)?),
+        Bound::Excluded(key) => Bound::Excluded(encode::<K>(key)?),
+        Bound::Unbounded => Bound::Unbounded,
+    })
+}
+
+#[inline]
+fn encode<K: Codec + ?Sized>(key: &K::Item) -> Result<Vec<u8>> {
+    Ok(K::encode(key).map_err(Error::from_encode)?.into_owned())
+}
+
+fn decode_item<'access, K, V>(
+    (key, val): RawItem<'access>,
+) -> Result<(K::Decoded<'access>, V::Decoded<'access>)>
+where
+    K: Codec + ?Sized + 'access,
+    V: Codec + ?Sized + 'access,
+{
+    Ok((
+        K::decode(key).map_err(Error::from_decode)?,
+        V::decode(val).map_err(Error::from_decode)?,
+    ))
+}
+
+/// Positions `cursor` on the first entry at or after `start`.
+///
+/// LMDB does not store empty keys and rejects them as seek targets, so an
+/// empty bound is treated as lying before every key.
+fn seek_start<'access, 'txn, K, V, L>(
+    cursor: &mut Cursor<'txn, '_, K, V, L>,
+    access: &'access ConstAccessor<'txn>,
+    start: Bound<&[u8]>,
+    op: &'static str,
+) -> Result<RawItem<'access>>
+where
+    K: ?Sized,
+    V: ?Sized,
+    L: Layout,
+{
+    let access = access.as_lmdb();
+    let kv = match start {
+        Bound::Unbounded => cursor.0.first(access),
+        Bound::Included([]) | Bound::Excluded([]) => cursor.0.first(access),
+        Bound::Included(key) => cursor.0.seek_range_k(access, key),
+        Bound::Excluded(key) => match cursor.0.seek_range_k::<[u8], [u8]>(access, key) {
+            Ok(kv) if cursor.cmp_keys(kv.0, key) == Ordering::Equal => cursor.0.next_nodup(access),
+            kv => kv,
+        },
+    };
+    kv.context(op, cursor.db_name())
+}
+
+/// Positions `cursor` on the last entry (the last duplicate, for dupsort
+/// databases) at or before `end`.
+fn seek_end<'access, 'txn, K, V, L>(
+    cursor: &mut Cursor<'txn, '_, K, V, L>,
+    access: &'access ConstAccessor<'txn>,
+    end: &Bound<Vec<u8>>,
+    op: &'static str,
+) -> Result<RawItem<'access>>
+where
+    K: ?Sized,
+    V: ?Sized,
+    L: Layout,
+{
+    let access = access.as_lmdb();
+    let key = match end {
+        Bound::Unbounded => return cursor.0.last(access).context(op, cursor.db_name()),
+        Bound::Included(key) | Bound::Excluded(key) => key,
+    };
+    if key.is_empty() {
+        return Err(lmdb_zero::Error::Code(error::NOTFOUND)).context(op, cursor.db_name());
+    }
+    let kv = cursor
+        .0
+        .seek_range_k::<[u8], [u8]>(access, &key[..])
+        .context(op, cursor.db_name())
+        .to_opt()?;
+    let kv = match kv {
+        None => cursor.0.last(access),
+        Some(kv)
+            if matches!(end, Bound::Included(_))
+                && cursor.cmp_keys(kv.0, key) == Ordering::Equal =>
+        {
+            let next = cursor
+                .0
+                .next_nodup::<[u8], [u8]>(access)
+                .context(op, cursor.db_name())
+                .to_opt()?;
+            match next {
+                Some(_) => cursor.0.prev(access),
+                None => cursor.0.last(access),
+            }
+        }
+        Some(_) => cursor.0.prev(access),
+    };
+    kv.context(op, cursor.db_name())
+}
+
+fn before_end<K, V, L>(cursor: &Cursor<K, V, L>, key: &[u8], end: &Bound<Vec<u8>>) -> bool
+where
+    K: ?Sized,
+    V: ?Sized,
+    L: Layout,
+{
+    match end {
+        Bound::Unbounded => true,
+        Bound::Included(end) => cursor.cmp_keys(key, end) != Ordering::Greater,
+        Bound::Excluded(end) => cursor.cmp_keys(key, end) == Ordering::Less,
+    }
+}
+
+fn after_start<K, V, L>(cursor: &Cursor<K, V, L>, key: &[u8], start: &Bound<Vec<u8>>) -> bool
+where
+    K: ?Sized,
+    V: ?Sized,
+    L: Layout,
+{
+    match start {
+        Bound::Unbounded => true,
+        Bound::Included(start) => cursor.cmp_keys(key, start) != Ordering::Less,
+        Bound::Excluded(start) => cursor.cmp_keys(key, start) == Ordering::Greater,
+    }
+}
+
+pub struct RangeIter<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L>
+where
+    L: Layout,
+    K: ?Sized,
+    V: ?Sized,
+{
+    cursor: MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>,
+    access: &'access ConstAccessor<'txn>,
+    start: Bound<Vec<u8>>,
+    end: Bound<Vec<u8>>,
+    started: bool,
+    done: bool,
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> RangeIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+{
+    #[inline]
+    pub fn new<R: RangeBounds<K::Item>>(
+        cursor: MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>,
+        access: &'access ConstAccessor<'txn>,
+        range: R,
+    ) -> Result<Self> {
+        let start = encode_bound::<K>(range.start_bound()).context("range", cursor.db_name())?;
+        let end = encode_bound::<K>(range.end_bound()).context("range", cursor.db_name())?;
+        Ok(RangeIter {
+            cursor,
+            access,
+            start,
+            end,
+            started: false,
+            done: false,
+        })
+    }
+
+    /// Yields owned copies of the entries, which outlive the transaction.
+    #[inline]
+    pub fn owned(self) -> OwnedIter<'access, Self, K, V>
+    where
+        K: CodecOwned + 'access,
+        V: CodecOwned + 'access,
+    {
+        OwnedIter::new(self)
+    }
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> Iterator
+    for RangeIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout,
+    K: Codec + ?Sized + 'access,
+    V: Codec + ?Sized + 'access,
+{
+    type Item = Result<(K::Decoded<'access>, V::Decoded<'access>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let kv = if self.started {
+            self.cursor
+                .0
+                .next(self.access.as_lmdb())
+                .context("range", self.cursor.db_name())
+        } else {
+            self.started = true;
+            seek_start(
+                &mut self.cursor,
+                self.access,
+                self.start.as_ref().map(Vec::as_slice),
+                "range",
+            )
+        };
+        match kv.to_opt() {
+            Ok(Some(kv)) if before_end(&self.cursor, kv.0, &self.end) => {
+                Some(decode_item::<K, V>(kv).context("range", self.cursor.db_name()))
+            }
+            Ok(_) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// A range iterator that can be consumed from both ends. It drives one cursor
+/// forward from the start of the range and a second one backward from its
+/// end, and stops once the two meet.
+pub struct DoubleEndedIter<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L>
+where
+    L: Layout,
+    K: ?Sized,
+    V: ?Sized,
+{
+    front: MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>,
+    back: MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>,
+    access: &'access ConstAccessor<'txn>,
+    start: Bound<Vec<u8>>,
+    end: Bound<Vec<u8>>,
+    front_pos: Option<RawItem<'access>>,
+    back_pos: Option<RawItem<'access>>,
+    done: bool,
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L>
+    DoubleEndedIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+{
+    #[inline]
+    pub fn new<R: RangeBounds<K::Item>>(
+        front: MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>,
+        back: MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>,
+        access: &'access ConstAccessor<'txn>,
+        range: R,
+    ) -> Result<Self> {
+        let start = encode_bound::<K>(range.start_bound()).context("range", front.db_name())?;
+        let end = encode_bound::<K>(range.end_bound()).context("range", front.db_name())?;
+        Ok(DoubleEndedIter {
+            front,
+            back,
+            access,
+            start,
+            end,
+            front_pos: None,
+            back_pos: None,
+            done: false,
+        })
+    }
+
+    /// Yields owned copies of the entries, which outlive the transaction.
+    #[inline]
+    pub fn owned(self) -> OwnedIter<'access, Self, K, V>
+    where
+        K: CodecOwned + 'access,
+        V: CodecOwned + 'access,
+    {
+        OwnedIter::new(self)
+    }
+
+    fn step(
+        &mut self,
+        kv: Result<RawItem<'access>>,
+        forward: bool,
+    ) -> Option<Result<RawItem<'access>>> {
+        let kv = match kv.to_opt() {
+            Ok(Some(kv)) => kv,
+            Ok(None) => {
+                self.done = true;
+                return None;
+            }
+            Err(err) => {
+                self.done = true;
+                return Some(Err(err));
+            }
+        };
+        let (in_range, other) = if forward {
+            (before_end(&self.front, kv.0, &self.end), self.back_pos)
+        } else {
+            (after_start(&self.back, kv.0, &self.start), self.front_pos)
+        };
+        if !in_range || other.is_some_and(|other| same_entry(other, kv)) {
+            self.done = true;
+            return None;
+        }
+        if forward {
+            self.front_pos = Some(kv);
+        } else {
+            self.back_pos = Some(kv);
+        }
+        Some(Ok(kv))
+    }
+}
+
+/// Whether two cursors of one transaction are on the same entry. LMDB
+/// returns keys and values in place in its pages, so an entry's position is
+/// where its key and value are, whatever their contents.
+#[inline]
+fn same_entry(a: RawItem, b: RawItem) -> bool {
+    ptr::eq(a.0, b.0) && ptr::eq(a.1, b.1)
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> Iterator
+    for DoubleEndedIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout,
+    K: Codec + ?Sized + 'access,
+    V: Codec + ?Sized + 'access,
+{
+    type Item = Result<(K::Decoded<'access>, V::Decoded<'access>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let kv = if self.front_pos.is_some() {
+            self.front
+                .0
+                .next(self.access.as_lmdb())
+                .context("range", self.front.db_name())
+        } else {
+            seek_start(
+                &mut self.front,
+                self.access,
+                self.start.as_ref().map(Vec::as_slice),
+                "range",
+            )
+        };
+        let item = self.step(kv, true)?.and_then(decode_item::<K, V>);
+        Some(item.context("range", self.front.db_name()))
+    }
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> DoubleEndedIterator
+    for DoubleEndedIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout,
+    K: Codec + ?Sized + 'access,
+    V: Codec + ?Sized + 'access,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let kv = if self.back_pos.is_some() {
+            self.back
+                .0
+                .prev(self.access.as_lmdb())
+                .context("range", self.back.db_name())
+        } else {
+            seek_end(&mut self.back, self.access, &self.end, "range")
+        };
+        let item = self.step(kv, false)?.and_then(decode_item::<K, V>);
+        Some(item.context("range", self.back.db_name()))
+    }
+}
+
+/// Iterates over the entries whose encoded keys start with a given prefix.
+pub struct PrefixIter<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L>
+where
+    L: Layout,
+    K: ?Sized,
+    V: ?Sized,
+{
+    cursor: MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>,
+    access: &'access ConstAccessor<'txn>,
+    prefix: Vec<u8>,
+    started: bool,
+    done: bool,
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> PrefixIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+{
+    #[inline]
+    pub fn new<P>(
+        cursor: MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>,
+        access: &'access ConstAccessor<'txn>,
+        prefix: &P,
+    ) -> Result<Self>
+    where
+        K: Prefix<P>,
+        P: ?Sized,
+    {
+        let prefix = K::encode_prefix(prefix).encode_context("prefix", cursor.db_name())?;
+        Ok(PrefixIter {
+            cursor,
+            access,
+            prefix: prefix.into_owned(),
+            started: false,
+            done: false,
+        })
+    }
+
+    /// Yields owned copies of the entries, which outlive the transaction.
+    #[inline]
+    pub fn owned(self) -> OwnedIter<'access, Self, K, V>
+    where
+        K: CodecOwned + 'access,
+        V: CodecOwned + 'access,
+    {
+        OwnedIter::new(self)
+    }
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> Iterator
+    for PrefixIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout,
+    K: Codec + ?Sized + 'access,
+    V: Codec + ?Sized + 'access,
+{
+    type Item = Result<(K::Decoded<'access>, V::Decoded<'access>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let kv = if self.started {
+            self.cursor
+                .0
+                .next(self.access.as_lmdb())
+                .context("prefix", self.cursor.db_name())
+        } else {
+            self.started = true;
+            seek_start(
+                &mut self.cursor,
+                self.access,
+                Bound::Included(&self.prefix),
+                "prefix",
+            )
+        };
+        match kv.to_opt() {
+            Ok(Some(kv)) if kv.0.starts_with(&self.prefix) => {
+                Some(decode_item::<K, V>(kv).context("prefix", self.cursor.db_name()))
+            }
+            Ok(_) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+type SharedCursor<'a, 'txn, 'db, K, V, L> = Rc<RefCell<MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>>>;
+
+/// Returns whether `cursor` is positioned on exactly `key`/`val`; if not, a
+/// `DupIter` or `GroupIter` sharing it must seek back before moving on.
+fn is_at<K, V, L>(
+    cursor: &mut Cursor<K, V, L>,
+    access: &ConstAccessor,
+    key: &[u8],
+    val: Option<&[u8]>,
+) -> bool
+where
+    K: ?Sized,
+    V: ?Sized,
+    L: Layout,
+{
+    match cursor.0.get_current::<[u8], [u8]>(access.as_lmdb()) {
+        Ok((k, v)) => k == key && val.is_none_or(|val| v == val),
+        Err(_) => false,
+    }
+}
+
+/// Iterates over the values stored under a single key of a dupsort database.
+pub struct DupIter<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L>
+where
+    L: Layout + LayoutDupsort,
+    K: ?Sized,
+    V: ?Sized,
+{
+    cursor: SharedCursor<'a, 'txn, 'db, K, V, L>,
+    access: &'access ConstAccessor<'txn>,
+    key: &'access [u8],
+    head: Option<&'access [u8]>,
+    last: Option<&'access [u8]>,
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> DupIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout + LayoutDupsort,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+{
+    #[inline]
+    pub fn new(
+        cursor: MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>,
+        access: &'access ConstAccessor<'txn>,
+        key: &K::Item,
+    ) -> Result<Self> {
+        let mut cursor = cursor;
+        let key = K::encode(key).encode_context("dups", cursor.db_name())?;
+        let found: Option<RawItem> = cursor
+            .0
+            .seek_k_both(access.as_lmdb(), &key[..])
+            .context("dups", cursor.db_name())
+            .to_opt()?;
+        Ok(DupIter {
+            cursor: Rc::new(RefCell::new(cursor)),
+            access,
+            key: found.map_or(&[][..], |(key, _)| key),
+            head: found.map(|(_, val)| val),
+            last: None,
+        })
+    }
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> Iterator
+    for DupIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout + LayoutDupsort,
+    K: Codec + ?Sized + 'access,
+    V: Codec + ?Sized + 'access,
+{
+    type Item = Result<V::Decoded<'access>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let val = match (self.head.take(), self.last) {
+            (Some(head), _) => head,
+            (None, None) => return None,
+            (None, Some(last)) => {
+                let mut cursor = self.cursor.borrow_mut();
+                let access = self.access.as_lmdb();
+                if !is_at(&mut cursor, self.access, self.key, Some(last)) {
+                    if let Err(err) = cursor.0.seek_kv(self.key, last) {
+                        self.last = None;
+                        return Some(Err(err).context("dups", cursor.db_name()));
+                    }
+                }
+                let val = cursor.0.next_dup::<[u8], [u8]>(access);
+                match val.context("dups", cursor.db_name()).to_opt() {
+                    Ok(Some((_, val))) => val,
+                    Ok(None) => {
+                        self.last = None;
+                        return None;
+                    }
+                    Err(err) => {
+                        self.last = None;
+                        return Some(Err(err));
+                    }
+                }
+            }
+        };
+        self.last = Some(val);
+        let cursor = self.cursor.borrow();
+        Some(V::decode(val).decode_context("dups", cursor.db_name()))
+    }
+}
+
+/// Iterates over the distinct keys of a dupsort database, pairing each with a
+/// `DupIter` over its values. The `DupIter`s share the underlying cursor, and
+/// each may be consumed at any time, even after the `GroupIter` has moved on.
+pub struct GroupIter<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L>
+where
+    L: Layout + LayoutDupsort,
+    K: ?Sized,
+    V: ?Sized,
+{
+    cursor: SharedCursor<'a, 'txn, 'db, K, V, L>,
+    access: &'access ConstAccessor<'txn>,
+    last: Option<&'access [u8]>,
+    done: bool,
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> GroupIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout + LayoutDupsort,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+{
+    #[inline]
+    pub fn new(
+        cursor: MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>,
+        access: &'access ConstAccessor<'txn>,
+    ) -> Self {
+        GroupIter {
+            cursor: Rc::new(RefCell::new(cursor)),
+            access,
+            last: None,
+            done: false,
+        }
+    }
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> Iterator
+    for GroupIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout + LayoutDupsort,
+    K: Codec + ?Sized + 'access,
+    V: Codec + ?Sized + 'access,
+{
+    type Item = Result<(
+        K::Decoded<'access>,
+        DupIter<'a, 'access, 'txn, 'db, K, V, L>,
+    )>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let kv = {
+            let mut cursor = self.cursor.borrow_mut();
+            let access = self.access.as_lmdb();
+            match self.last {
+                None => cursor.0.first(access),
+                Some(last) => {
+                    if is_at(&mut cursor, self.access, last, None) {
+                        cursor.0.next_nodup(access)
+                    } else {
+                        cursor
+                            .0
+                            .seek_k_both::<[u8], [u8]>(access, last)
+                            .and_then(|_| cursor.0.next_nodup(access))
+                    }
+                }
+            }
+        };
+        let cursor = self.cursor.borrow();
+        let (key, val): RawItem = match kv.context("groups", cursor.db_name()).to_opt() {
+            Ok(Some(kv)) => kv,
+            Ok(None) => {
+                self.done = true;
+                return None;
+            }
+            Err(err) => {
+                self.done = true;
+                return Some(Err(err));
+            }
+        };
+        self.last = Some(key);
+        let dups = DupIter {
+            cursor: self.cursor.clone(),
+            access: self.access,
+            key,
+            head: Some(val),
+            last: None,
+        };
+        Some(
+            K::decode(key)
+                .decode_context("groups", cursor.db_name())
+                .map(|key| (key, dups)),
+        )
+    }
+}
+
+/// Iterates over the values stored under a single key of a dupfixed database
+/// a page at a time, via `get_multiple`/`next_multiple`.
+pub struct PageIter<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L>
+where
+    L: Layout + LayoutDupsort + LayoutDupfixed,
+    K: ?Sized,
+{
+    cursor: MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>,
+    access: &'access ConstAccessor<'txn>,
+    started: bool,
+    done: bool,
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> PageIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout + LayoutDupsort + LayoutDupfixed,
+    K: Codec + ?Sized,
+    [V]: FromLmdbBytes,
+{
+    #[inline]
+    pub fn new(
+        cursor: MaybeOwned<'a, Cursor<'txn, 'db, K, V, L>>,
+        access: &'access ConstAccessor<'txn>,
+        key: &K::Item,
+    ) -> Result<Self> {
+        let mut cursor = cursor;
+        let key = K::encode(key).encode_context("pages", cursor.db_name())?;
+        let found = cursor
+            .0
+            .seek_k::<[u8], [u8]>(access.as_lmdb(), &key)
+            .context("pages", cursor.db_name())
+            .to_opt()?
+            .is_some();
+        Ok(PageIter {
+            cursor,
+            access,
+            started: false,
+            done: !found,
+        })
+    }
+
+    /// Flattens the pages into the individual values.
+    #[inline]
+    pub fn values(self) -> PageValues<'a, 'access, 'txn, 'db, K, V, L> {
+        PageValues {
+            pages: self,
+            page: [].iter(),
+        }
+    }
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> Iterator
+    for PageIter<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout + LayoutDupsort + LayoutDupfixed,
+    K: ?Sized,
+    [V]: FromLmdbBytes,
+    V: 'access,
+{
+    type Item = Result<&'access [V]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let access = self.access.as_lmdb();
+        let page = if self.started {
+            self.cursor.0.next_multiple(access)
+        } else {
+            self.started = true;
+            self.cursor.0.get_multiple(access)
+        };
+        match page.decode_context("pages", self.cursor.db_name()).to_opt() {
+            Ok(Some(page)) => Some(Ok(page)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// The values of a `PageIter`, one at a time.
+pub struct PageValues<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L>
+where
+    L: Layout + LayoutDupsort + LayoutDupfixed,
+    K: ?Sized,
+{
+    pages: PageIter<'a, 'access, 'txn, 'db, K, V, L>,
+    page: std::slice::Iter<'access, V>,
+}
+
+impl<'a, 'access: 'a, 'txn: 'access, 'db: 'txn, K, V, L> Iterator
+    for PageValues<'a, 'access, 'txn, 'db, K, V, L>
+where
+    L: Layout + LayoutDupsort + LayoutDupfixed,
+    K: ?Sized,
+    [V]: FromLmdbBytes,
+    V: 'access,
+{
+    type Item = Result<&'access V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(val) = self.page.next() {
+                return Some(Ok(val));
+            }
+            match self.pages.next()? {
+                Ok(page) => self.page = page.iter(),
+                Err(err) => return Some(Err(err)),
             }
         }
     }
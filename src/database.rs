@@ -1,7 +1,10 @@
-use lmdb_zero::{Environment, Result};
+use std::sync::Arc;
+
+use lmdb_zero::{db, Environment};
 
 use supercow::Supercow;
 
+use crate::error::{Error, Result, WithContext};
 use crate::Layout;
 
 #[derive(Debug)]
@@ -10,6 +13,7 @@ pub struct Database<'e, K: ?Sized, V: ?Sized, L: Layout>(
     std::marker::PhantomData<K>,
     std::marker::PhantomData<V>,
     std::marker::PhantomData<L>,
+    Option<Arc<str>>,
 );
 
 impl<'e, K: ?Sized, V: ?Sized, L: Layout> Database<'e, K, V, L> {
@@ -20,6 +24,7 @@ impl<'e, K: ?Sized, V: ?Sized, L: Layout> Database<'e, K, V, L> {
             std::marker::PhantomData,
             std::marker::PhantomData,
             std::marker::PhantomData,
+            None,
         )
     }
 
@@ -32,14 +37,59 @@ impl<'e, K: ?Sized, V: ?Sized, L: Layout> Database<'e, K, V, L> {
     where
         E: Into<Supercow<'e, Environment>>,
     {
-        Ok(Database::from_lmdb::<E>(lmdb_zero::Database::open(
-            env, name, options,
-        )?))
+        // Reject explicit flags that disagree with the layout before CREATE
+        // can make a database with them.
+        let requested = options.flags & (db::DUPSORT | db::DUPFIXED);
+        if !L::flags().contains(requested) {
+            return Err(Error::LayoutMismatch {
+                name: name.map(str::to_owned),
+                expected: L::flags(),
+                actual: requested,
+            });
+        }
+
+        // Typed cursors and imports compare encoded keys by the database
+        // flags, so they cannot follow a comparator set by `sort_keys_as` or
+        // `sort_values_as`.
+        if has_comparator(options) {
+            return Err(Error::CustomComparator {
+                name: name.map(str::to_owned),
+            });
+        }
+
+        let mut options = options.clone();
+        if options.flags.contains(db::CREATE) {
+            options.flags.insert(L::flags());
+        }
+
+        let db_name = name.map(Arc::from);
+        let mut db = Database::from_lmdb::<E>(
+            lmdb_zero::Database::open(env, name, &options).context("open", db_name.as_ref())?,
+        );
+        db.4 = db_name;
+        let stored = lmdb_zero::ReadTransaction::new(db.env())
+            .and_then(|txn| txn.db_flags(&db.0))
+            .context("open", db.4.as_ref())?;
+        check_flags::<L>(name, options.flags, stored)?;
+        Ok(db)
     }
 
     #[inline]
     pub fn delete(self) -> Result<()> {
-        self.0.delete()
+        let name = self.4;
+        self.0.delete().context("delete", name.as_ref())
+    }
+
+    /// The name the database was opened with, or `None` for the main
+    /// database and databases wrapped with `from_lmdb`.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.4.as_deref()
+    }
+
+    #[inline]
+    pub(crate) fn name_arc(&self) -> Option<&Arc<str>> {
+        self.4.as_ref()
     }
 
     #[inline]
@@ -62,3 +112,30 @@ impl<'e, K: ?Sized, V: ?Sized, L: Layout> Database<'e, K, V, L> {
         self.0
     }
 }
+
+/// Checks the stored flags of an opened database against its layout and the
+/// requested key ordering.
+fn check_flags<L: Layout>(
+    name: Option<&str>,
+    requested: db::Flags,
+    stored: db::Flags,
+) -> Result<()> {
+    let checked = db::DUPSORT | db::DUPFIXED | db::INTEGERKEY | db::REVERSEKEY;
+    let expected = L::flags() | (requested & (db::INTEGERKEY | db::REVERSEKEY));
+    let actual = stored & checked;
+    if actual != expected {
+        return Err(Error::LayoutMismatch {
+            name: name.map(str::to_owned),
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Whether `options` sets a key or value comparator. `DatabaseOptions` keeps
+/// them private, so this goes by its `Debug` output.
+fn has_comparator(options: &lmdb_zero::DatabaseOptions) -> bool {
+    let debug = format!("{:?}", options);
+    !(debug.contains("key_cmp: None") && debug.contains("val_cmp: None"))
+}
@@ -0,0 +1,291 @@
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+
+use lmdb_zero::db;
+use lmdb_zero::traits::LmdbResultExt;
+
+use crate::error::{Error, Result, WithContext};
+use crate::{ConstAccessor, ConstTransaction, Database, Layout, WriteAccessor};
+
+/// The flags `mdb_dump` records in its header, by name.
+const FLAGS: [(db::Flags, &str); 6] = [
+    (db::REVERSEKEY, "reversekey"),
+    (db::DUPSORT, "dupsort"),
+    (db::INTEGERKEY, "integerkey"),
+    (db::DUPFIXED, "dupfixed"),
+    (db::INTEGERDUP, "integerdup"),
+    (db::REVERSEDUP, "reversedup"),
+];
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/// How `dump_with_format` writes keys and values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// Every byte as two hex digits, like `mdb_dump`.
+    Bytes,
+    /// Printable characters as they are and other bytes escaped, like
+    /// `mdb_dump -p`.
+    Print,
+}
+
+/// Writes the contents of `db` in the text format of `mdb_dump`, so that it
+/// can be restored with `load` or `mdb_load`. Keys and values are written as
+/// hex bytes, as `mdb_dump` does by default.
+///
+/// `access` must be the accessor of `txn`, which is needed as well to open a
+/// cursor; another transaction's fails with `lmdb_zero::Error::Mismatch`.
+#[inline]
+pub fn dump<'txn, K, V, L, W>(
+    txn: &'txn ConstTransaction,
+    access: &ConstAccessor<'txn>,
+    db: &Database<K, V, L>,
+    out: W,
+) -> Result<()>
+where
+    K: ?Sized,
+    V: ?Sized,
+    L: Layout,
+    W: Write,
+{
+    dump_with_format(txn, access, db, DumpFormat::Bytes, out)
+}
+
+/// Like `dump`, but writes keys and values in `format`.
+pub fn dump_with_format<'txn, K, V, L, W>(
+    txn: &'txn ConstTransaction,
+    access: &ConstAccessor<'txn>,
+    db: &Database<K, V, L>,
+    format: DumpFormat,
+    out: W,
+) -> Result<()>
+where
+    K: ?Sized,
+    V: ?Sized,
+    L: Layout,
+    W: Write,
+{
+    let name = db.name_arc();
+    let info = db.env().info().context("dump", name)?;
+    let stat = txn.db_stat(db)?;
+    let flags = txn.db_flags(db)?;
+
+    let mut out = BufWriter::new(out);
+    writeln!(out, "VERSION=3")?;
+    match format {
+        DumpFormat::Bytes => writeln!(out, "format=bytevalue")?,
+        DumpFormat::Print => writeln!(out, "format=print")?,
+    }
+    if let Some(name) = db.name() {
+        writeln!(out, "database={}", name)?;
+    }
+    writeln!(out, "type=btree")?;
+    writeln!(out, "mapsize={}", info.mapsize)?;
+    writeln!(out, "maxreaders={}", info.maxreaders)?;
+    if flags.contains(db::DUPSORT) {
+        writeln!(out, "duplicates=1")?;
+    }
+    for (flag, flag_name) in FLAGS {
+        if flags.contains(flag) {
+            writeln!(out, "{}=1", flag_name)?;
+        }
+    }
+    writeln!(out, "db_pagesize={}", stat.psize)?;
+    writeln!(out, "HEADER=END")?;
+
+    let mut cursor = txn.cursor(db)?;
+    let mut item = cursor
+        .0
+        .first::<[u8], [u8]>(access.as_lmdb())
+        .to_opt()
+        .context("dump", name)?;
+    while let Some((key, val)) = item {
+        write_value(&mut out, key, format)?;
+        write_value(&mut out, val, format)?;
+        item = cursor
+            .0
+            .next::<[u8], [u8]>(access.as_lmdb())
+            .to_opt()
+            .context("dump", name)?;
+    }
+    writeln!(out, "DATA=END")?;
+    out.flush()?;
+    Ok(())
+}
+
+fn write_value<W: Write>(out: &mut W, bytes: &[u8], format: DumpFormat) -> Result<()> {
+    out.write_all(b" ")?;
+    for &byte in bytes {
+        match format {
+            DumpFormat::Print if byte == b'\\' => out.write_all(b"\\\\")?,
+            DumpFormat::Print if byte == b' ' || byte.is_ascii_graphic() => {
+                out.write_all(&[byte])?
+            }
+            DumpFormat::Print => {
+                out.write_all(&[b'\\', HEX[byte as usize >> 4], HEX[byte as usize & 0xf]])?
+            }
+            DumpFormat::Bytes => {
+                out.write_all(&[HEX[byte as usize >> 4], HEX[byte as usize & 0xf]])?
+            }
+        }
+    }
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Reads records in the text format of `mdb_dump` into `db`, returning the
+/// number of records read.
+///
+/// Only the first database in the input is read. Its `dupsort` and key
+/// ordering flags must match those of `db`; the database name and
+/// environment settings in the header are ignored. As with `dump`, `access`
+/// must be the accessor of `txn`.
+pub fn load<'txn, K, V, L, R>(
+    txn: &'txn ConstTransaction,
+    access: &mut WriteAccessor<'txn>,
+    db: &Database<K, V, L>,
+    input: R,
+) -> Result<u64>
+where
+    K: ?Sized,
+    V: ?Sized,
+    L: Layout,
+    R: Read,
+{
+    let name = db.name_arc();
+    let mut lines = BufReader::new(input).split(b'\n');
+    let mut line_no = 0;
+    let mut next_line = |what: &str| -> Result<(usize, Vec<u8>)> {
+        line_no += 1;
+        let Some(text) = lines.next() else {
+            let message = format!("unexpected end of input, expected {}", what);
+            return Err(dump_error(line_no, message));
+        };
+        let mut text = text?;
+        if text.last() == Some(&b'\r') {
+            text.pop();
+        }
+        Ok((line_no, text))
+    };
+
+    let mut format = DumpFormat::Bytes;
+    let mut flags = db::Flags::empty();
+    let header_end = loop {
+        let (line, text) = next_line("header")?;
+        if text == b"HEADER=END" {
+            break line;
+        }
+        let text = String::from_utf8_lossy(&text);
+        let Some((key, value)) = text.split_once('=') else {
+            return Err(dump_error(line, format!("invalid header line {:?}", text)));
+        };
+        match key {
+            "VERSION" if value != "3" => {
+                return Err(dump_error(line, format!("unsupported version {}", value)))
+            }
+            "format" => {
+                format = match value {
+                    "bytevalue" => DumpFormat::Bytes,
+                    "print" => DumpFormat::Print,
+                    _ => return Err(dump_error(line, format!("unknown format {}", value))),
+                }
+            }
+            "type" if value != "btree" => {
+                return Err(dump_error(line, format!("unsupported type {}", value)))
+            }
+            "duplicates" if value == "1" => flags.insert(db::DUPSORT),
+            _ => {
+                if let Some((flag, _)) = FLAGS.iter().find(|(_, flag_name)| *flag_name == key) {
+                    if value == "1" {
+                        flags.insert(*flag);
+                    }
+                }
+            }
+        }
+    };
+
+    let checked = FLAGS
+        .iter()
+        .fold(db::Flags::empty(), |all, (flag, _)| all | *flag);
+    let actual = txn.db_flags(db)? & checked;
+    if flags != actual {
+        return Err(dump_error(
+            header_end,
+            format!(
+                "dump has flags {:?}, but the database has {:?}",
+                flags, actual
+            ),
+        ));
+    }
+
+    let mut count = 0;
+    loop {
+        let (line, key) = next_line("data")?;
+        if key == b"DATA=END" {
+            return Ok(count);
+        }
+        let key = parse_value(line, &key, format)?;
+        let (line, val) = next_line("value")?;
+        let val = parse_value(line, &val, format)?;
+        access
+            .as_lmdb_mut()
+            .put(&db.0, &key[..], &val[..], lmdb_zero::put::Flags::empty())
+            .context("load", name)?;
+        count += 1;
+    }
+}
+
+fn parse_value(line: usize, text: &[u8], format: DumpFormat) -> Result<Vec<u8>> {
+    let Some(text) = text.strip_prefix(b" ") else {
+        return Err(dump_error(
+            line,
+            "expected a line starting with a space".to_owned(),
+        ));
+    };
+    let hex_pair = |pair: &[u8]| -> Result<u8> {
+        match pair {
+            [hi, lo] if hi.is_ascii_hexdigit() && lo.is_ascii_hexdigit() => {
+                Ok((hex_digit(*hi) << 4) | hex_digit(*lo))
+            }
+            _ => Err(dump_error(
+                line,
+                format!("invalid hex {:?}", String::from_utf8_lossy(pair)),
+            )),
+        }
+    };
+
+    match format {
+        DumpFormat::Bytes if text.len() % 2 != 0 => {
+            Err(dump_error(line, "odd number of hex digits".to_owned()))
+        }
+        DumpFormat::Bytes => text.chunks(2).map(hex_pair).collect(),
+        DumpFormat::Print => {
+            let mut bytes = Vec::with_capacity(text.len());
+            let mut rest = text;
+            while let Some((&byte, tail)) = rest.split_first() {
+                rest = tail;
+                if byte != b'\\' {
+                    bytes.push(byte);
+                } else if rest.first() == Some(&b'\\') {
+                    bytes.push(b'\\');
+                    rest = &rest[1..];
+                } else if rest.len() >= 2 {
+                    bytes.push(hex_pair(&rest[..2])?);
+                    rest = &rest[2..];
+                } else {
+                    return Err(dump_error(line, "truncated escape".to_owned()));
+                }
+            }
+            Ok(bytes)
+        }
+    }
+}
+
+#[inline]
+fn hex_digit(digit: u8) -> u8 {
+    (digit as char).to_digit(16).unwrap() as u8
+}
+
+#[inline]
+fn dump_error(line: usize, message: String) -> Error {
+    Error::Dump { line, message }
+}
@@ -0,0 +1,419 @@
+use std::any::{type_name, TypeId};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError, RwLock, RwLockReadGuard};
+
+use lmdb_zero::{error, open, DatabaseOptions, EnvBuilder, Environment, FileMode};
+
+use crate::error::{Error, Result, WithContext};
+use crate::{
+    AsLmdbError, ConstAccessor, Database, Layout, ReadTransaction, WriteAccessor, WriteTransaction,
+};
+
+/// Paths of the environments opened through `TypedEnvBuilder`. LMDB forbids
+/// opening the same environment twice in one process.
+static OPEN_PATHS: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+#[derive(Debug)]
+struct PathGuard(PathBuf);
+
+impl Drop for PathGuard {
+    fn drop(&mut self) {
+        OPEN_PATHS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.0);
+    }
+}
+
+thread_local! {
+    /// The resize locks this thread holds through `TypedEnv::read`/`write`.
+    static HELD_LOCKS: RefCell<Vec<*const RwLock<()>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A shared hold on a `TypedEnv`'s resize lock. Only the outermost `read` or
+/// `write` of a thread takes the lock, so nested ones cannot queue behind a
+/// pending resize that is waiting for the outer one to finish.
+struct ResizeHold<'a> {
+    lock: *const RwLock<()>,
+    guard: Option<RwLockReadGuard<'a, ()>>,
+}
+
+impl<'a> ResizeHold<'a> {
+    fn new(lock: &'a RwLock<()>) -> Self {
+        let ptr = lock as *const _;
+        if is_held(ptr) {
+            return ResizeHold {
+                lock: ptr,
+                guard: None,
+            };
+        }
+        let guard = lock.read().unwrap_or_else(PoisonError::into_inner);
+        HELD_LOCKS.with(|held| held.borrow_mut().push(ptr));
+        ResizeHold {
+            lock: ptr,
+            guard: Some(guard),
+        }
+    }
+}
+
+impl Drop for ResizeHold<'_> {
+    fn drop(&mut self) {
+        if self.guard.is_some() {
+            HELD_LOCKS.with(|held| held.borrow_mut().retain(|&lock| lock != self.lock));
+        }
+    }
+}
+
+fn is_held(lock: *const RwLock<()>) -> bool {
+    HELD_LOCKS.with(|held| held.borrow().contains(&lock))
+}
+
+#[derive(Debug)]
+struct DbTypes {
+    ids: (TypeId, TypeId, TypeId),
+    names: String,
+}
+
+impl DbTypes {
+    fn of<K, V, L>() -> Self
+    where
+        K: ?Sized + 'static,
+        V: ?Sized + 'static,
+        L: 'static,
+    {
+        DbTypes {
+            ids: (TypeId::of::<K>(), TypeId::of::<V>(), TypeId::of::<L>()),
+            names: format!(
+                "Database<{}, {}, {}>",
+                type_name::<K>(),
+                type_name::<V>(),
+                type_name::<L>()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TypedEnvBuilder {
+    map_size: Option<usize>,
+    max_dbs: Option<u32>,
+    max_readers: Option<u32>,
+    flags: open::Flags,
+    mode: FileMode,
+}
+
+impl TypedEnvBuilder {
+    #[inline]
+    pub fn new() -> Self {
+        TypedEnvBuilder {
+            map_size: None,
+            max_dbs: None,
+            max_readers: None,
+            flags: open::Flags::empty(),
+            mode: 0o600,
+        }
+    }
+
+    #[inline]
+    pub fn map_size(mut self, size: usize) -> Self {
+        self.map_size = Some(size);
+        self
+    }
+
+    #[inline]
+    pub fn max_dbs(mut self, dbs: u32) -> Self {
+        self.max_dbs = Some(dbs);
+        self
+    }
+
+    #[inline]
+    pub fn max_readers(mut self, readers: u32) -> Self {
+        self.max_readers = Some(readers);
+        self
+    }
+
+    #[inline]
+    pub fn flags(mut self, flags: open::Flags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    #[inline]
+    pub fn mode(mut self, mode: FileMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Opens the environment at `path`, failing with `Error::AlreadyOpen` if
+    /// this process already has it open through another `TypedEnv`.
+    pub fn open<P: AsRef<Path>>(self, path: P) -> Result<TypedEnv> {
+        let path = path.as_ref();
+        let canonical = canonical_path(path)?;
+
+        let mut open_paths = OPEN_PATHS.lock().unwrap_or_else(PoisonError::into_inner);
+        if open_paths.contains(&canonical) {
+            return Err(Error::AlreadyOpen(canonical));
+        }
+
+        let mut builder = EnvBuilder::new()?;
+        if let Some(size) = self.map_size {
+            builder.set_mapsize(size)?;
+        }
+        if let Some(dbs) = self.max_dbs {
+            builder.set_maxdbs(dbs)?;
+        }
+        if let Some(readers) = self.max_readers {
+            builder.set_maxreaders(readers)?;
+        }
+        // The path registry ensures no other `TypedEnv` in this process has
+        // the environment open.
+        let env = unsafe { builder.open(&path.to_string_lossy(), self.flags, self.mode)? };
+
+        open_paths.insert(canonical.clone());
+        let mut env = TypedEnv::from_lmdb(env);
+        env.path = Some(PathGuard(canonical));
+        Ok(env)
+    }
+}
+
+/// The canonical form of `path`, which with `NOSUBDIR` need not exist yet,
+/// so that every spelling of it is registered the same way.
+fn canonical_path(path: &Path) -> io::Result<PathBuf> {
+    match path.canonicalize() {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+                return Err(err);
+            };
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            Ok(parent.canonicalize()?.join(name))
+        }
+        result => result,
+    }
+}
+
+impl Default for TypedEnvBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How `TypedEnv::write_resizing` grows the map when a write runs out of
+/// space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapGrowth {
+    Double,
+    Step(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResizePolicy {
+    pub growth: MapGrowth,
+    /// The map is never grown beyond this many bytes.
+    pub max_size: Option<usize>,
+}
+
+impl ResizePolicy {
+    /// The size to grow a map of `current` bytes to, or `None` if the ceiling
+    /// has been reached.
+    pub fn next_size(&self, current: usize) -> Option<usize> {
+        let next = match self.growth {
+            MapGrowth::Double => current.saturating_mul(2),
+            MapGrowth::Step(step) => current.saturating_add(step),
+        };
+        let next = self.max_size.map_or(next, |max| next.min(max));
+        if next > current {
+            Some(next)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for ResizePolicy {
+    fn default() -> Self {
+        ResizePolicy {
+            growth: MapGrowth::Double,
+            max_size: None,
+        }
+    }
+}
+
+/// An LMDB environment with closure-based transactions and a registry of the
+/// types its named databases have been opened with.
+///
+/// Transactions started through `read`/`write` hold a shared lock that
+/// `write_resizing` takes exclusively to change the map size, as LMDB requires
+/// that no transaction be active in the process at that point. The lock is
+/// re-entrant per thread, so `read` and `write` may be nested.
+///
+/// Transactions created directly from the underlying `Environment`, through
+/// `as_lmdb` or `Deref`, are not covered: they must not be open on any thread
+/// while `write_resizing` may grow the map.
+#[derive(Debug)]
+pub struct TypedEnv {
+    env: Environment,
+    resize_lock: RwLock<()>,
+    databases: Mutex<HashMap<Option<String>, DbTypes>>,
+    path: Option<PathGuard>,
+}
+
+impl TypedEnv {
+    #[inline]
+    pub fn from_lmdb(env: Environment) -> TypedEnv {
+        TypedEnv {
+            env,
+            resize_lock: RwLock::new(()),
+            databases: Mutex::new(HashMap::new()),
+            path: None,
+        }
+    }
+
+    #[inline]
+    pub fn builder() -> TypedEnvBuilder {
+        TypedEnvBuilder::new()
+    }
+
+    /// Opens the database `name`, failing with `Error::TypeConflict` if it has
+    /// already been opened through this environment with different `K`, `V`
+    /// or `L`.
+    pub fn open_db<K, V, L>(
+        &self,
+        name: Option<&str>,
+        options: &DatabaseOptions,
+    ) -> Result<Database<'_, K, V, L>>
+    where
+        K: ?Sized + 'static,
+        V: ?Sized + 'static,
+        L: Layout + 'static,
+    {
+        let requested = DbTypes::of::<K, V, L>();
+        let mut databases = self
+            .databases
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let key = name.map(str::to_owned);
+        if let Some(registered) = databases.get(&key) {
+            if registered.ids != requested.ids {
+                return Err(Error::TypeConflict {
+                    name: key,
+                    registered: registered.names.clone(),
+                    requested: requested.names,
+                });
+            }
+        }
+
+        let db = Database::open(&self.env, name, options)?;
+        databases.insert(key, requested);
+        Ok(db)
+    }
+
+    /// Runs `f` in a new read transaction.
+    #[inline]
+    pub fn read<'env, T, E, F>(&'env self, f: F) -> std::result::Result<T, E>
+    where
+        F: for<'txn, 'a> FnOnce(
+            &'txn ReadTransaction<'env>,
+            &'a ConstAccessor<'txn>,
+        ) -> std::result::Result<T, E>,
+        E: From<Error>,
+    {
+        let _hold = ResizeHold::new(&self.resize_lock);
+        let txn = ReadTransaction::new(&self.env)?;
+        let access = txn.access();
+        f(&txn, &access)
+    }
+
+    /// Runs `f` in a new write transaction, committing it if `f` returns `Ok`
+    /// and aborting it otherwise.
+    #[inline]
+    pub fn write<'env, T, E, F>(&'env self, f: F) -> std::result::Result<T, E>
+    where
+        F: for<'txn, 'a> FnOnce(
+            &'txn WriteTransaction<'env>,
+            &'a mut WriteAccessor<'txn>,
+        ) -> std::result::Result<T, E>,
+        E: From<Error>,
+    {
+        let _hold = ResizeHold::new(&self.resize_lock);
+        let txn = WriteTransaction::new(&self.env)?;
+        let result = {
+            let mut access = txn.access();
+            f(&txn, &mut access)?
+        };
+        txn.commit()?;
+        Ok(result)
+    }
+
+    /// Like `write`, but if the transaction fails with `MAP_FULL` the map is
+    /// grown according to `policy` and `f` is run again in a fresh
+    /// transaction.
+    ///
+    /// Growing the map waits until every other `read`/`write` on this
+    /// environment has finished. Called from inside one on the same thread,
+    /// it cannot grow the map and returns the `MAP_FULL` error instead.
+    pub fn write_resizing<'env, T, E, F>(
+        &'env self,
+        policy: &ResizePolicy,
+        mut f: F,
+    ) -> std::result::Result<T, E>
+    where
+        F: for<'txn, 'a> FnMut(
+            &'txn WriteTransaction<'env>,
+            &'a mut WriteAccessor<'txn>,
+        ) -> std::result::Result<T, E>,
+        E: From<Error> + AsLmdbError,
+    {
+        loop {
+            match self.write(&mut f) {
+                Err(err) if is_map_full(&err) && !is_held(&self.resize_lock) => {
+                    let _guard = self
+                        .resize_lock
+                        .write()
+                        .unwrap_or_else(PoisonError::into_inner);
+                    let size = self.env.info().context("resize", None)?.mapsize;
+                    match policy.next_size(size) {
+                        // No transactions started through this `TypedEnv` are
+                        // active while we hold the lock exclusively.
+                        Some(size) => unsafe {
+                            self.env.set_mapsize(size).context("resize", None)?
+                        },
+                        None => return Err(err),
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    #[inline]
+    pub fn as_lmdb(&self) -> &Environment {
+        &self.env
+    }
+
+    /// Unwraps the environment. If it was opened by `TypedEnvBuilder`, its
+    /// path stays registered as open for the rest of the process.
+    #[inline]
+    pub fn into_lmdb(self) -> Environment {
+        std::mem::forget(self.path);
+        self.env
+    }
+}
+
+impl std::ops::Deref for TypedEnv {
+    type Target = Environment;
+
+    fn deref(&self) -> &Environment {
+        &self.env
+    }
+}
+
+fn is_map_full<E: AsLmdbError>(err: &E) -> bool {
+    err.lmdb_code() == Some(error::MAP_FULL)
+}
@@ -0,0 +1,359 @@
+use std::fmt;
+use std::io;
+use std::os::raw::c_int;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use lmdb_zero::{db, error};
+
+/// The operation and database an error was raised from, where known.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Context {
+    pub op: Option<&'static str>,
+    pub db: Option<Arc<str>>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// Any LMDB failure not covered by a more specific variant.
+    Lmdb {
+        error: lmdb_zero::Error,
+        context: Context,
+    },
+    /// The key or cursor position asked for holds no record (`MDB_NOTFOUND`).
+    /// The `*_opt` methods and `ResultExt::to_opt` turn it into `None`.
+    NotFound(Context),
+    /// A write needed more space than the environment's map size allows
+    /// (`MDB_MAP_FULL`); the write transaction should be aborted.
+    MapFull(Context),
+    /// A stored counter would pass the maximum of its type.
+    Overflow(Context),
+    /// A codec could not encode a key or value.
+    Encode {
+        message: String,
+        context: Context,
+    },
+    /// A codec rejected a stored key or value.
+    Decode {
+        message: String,
+        context: Context,
+    },
+    /// `Database::open` was given, or found stored, database flags that
+    /// differ from the ones its layout requires, e.g. a `DUPSORT` database
+    /// opened with `LmdbLayoutDefault`.
+    LayoutMismatch {
+        name: Option<String>,
+        expected: db::Flags,
+        actual: db::Flags,
+    },
+    /// `TypedEnv::open_db` was asked for a database it already opened with
+    /// different key or value types; `registered` and `requested` name them.
+    TypeConflict {
+        name: Option<String>,
+        registered: String,
+        requested: String,
+    },
+    /// A typed database was opened with a comparator from `sort_keys_as` or
+    /// `sort_values_as`, which its cursors and imports cannot follow.
+    CustomComparator {
+        name: Option<String>,
+    },
+    /// `TypedEnv` was asked to open an environment this process already has
+    /// open, which LMDB does not allow; the path is the canonical one.
+    AlreadyOpen(PathBuf),
+    /// A write through an `IndexedDatabase` would give a second record the
+    /// same key in a unique index; `key` is the encoded index key.
+    UniqueViolation {
+        index: Option<String>,
+        key: Vec<u8>,
+    },
+    /// A `BulkLoader` was given a record that does not sort after the one
+    /// before it, or after the data already in the database. `position`
+    /// counts the records loaded before it.
+    OutOfOrder {
+        db: Option<String>,
+        position: u64,
+        key: Vec<u8>,
+    },
+    /// An `Importer` rejecting duplicates was given two records the database
+    /// cannot both hold; `key` is their encoded key.
+    DuplicateKey {
+        db: Option<String>,
+        key: Vec<u8>,
+    },
+    Io(io::Error),
+    /// Input to `dump::load` is not in the format `mdb_dump` writes.
+    Dump {
+        line: usize,
+        message: String,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    #[inline]
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Error::NotFound(_))
+    }
+
+    #[inline]
+    pub fn context(&self) -> Option<&Context> {
+        match self {
+            Error::Lmdb { context, .. }
+            | Error::NotFound(context)
+            | Error::MapFull(context)
+            | Error::Overflow(context)
+            | Error::Encode { context, .. }
+            | Error::Decode { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Converts an error from `Codec::encode`, in which `ValRejected` means
+    /// the key or value could not be encoded.
+    pub(crate) fn from_encode(err: lmdb_zero::Error) -> Self {
+        match err {
+            lmdb_zero::Error::ValRejected(message) => Error::Encode {
+                message,
+                context: Context::default(),
+            },
+            err => err.into(),
+        }
+    }
+
+    /// Converts an error from `Codec::decode` or from reading a stored value,
+    /// in which `ValRejected` means the stored bytes could not be decoded.
+    pub(crate) fn from_decode(err: lmdb_zero::Error) -> Self {
+        match err {
+            lmdb_zero::Error::ValRejected(message) => Error::Decode {
+                message,
+                context: Context::default(),
+            },
+            err => err.into(),
+        }
+    }
+
+    /// Records `op` and `db` as where the error happened, unless an inner
+    /// call already did.
+    pub(crate) fn with_context(mut self, op: &'static str, db: Option<&Arc<str>>) -> Self {
+        if let Error::Lmdb { context, .. }
+        | Error::NotFound(context)
+        | Error::MapFull(context)
+        | Error::Overflow(context)
+        | Error::Encode { context, .. }
+        | Error::Decode { context, .. } = &mut self
+        {
+            if context.op.is_none() {
+                context.op = Some(op);
+                context.db = db.cloned();
+            }
+        }
+        self
+    }
+}
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(op) = self.op {
+            write!(f, "{}", op)?;
+            if let Some(db) = &self.db {
+                write!(f, " on database {}", db)?;
+            }
+            write!(f, ": ")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Lmdb { error, context } => write!(f, "{}{}", context, error),
+            Error::NotFound(context) => write!(f, "{}key not found", context),
+            Error::MapFull(context) => write!(f, "{}map is full", context),
+            Error::Overflow(context) => write!(f, "{}counter overflow", context),
+            Error::Encode { message, context } => write!(f, "{}{}", context, message),
+            Error::Decode { message, context } => write!(f, "{}{}", context, message),
+            Error::LayoutMismatch {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "database {} has flags {:?}, but its type requires {:?}",
+                name.as_deref().unwrap_or("<main>"),
+                actual,
+                expected
+            ),
+            Error::TypeConflict {
+                name,
+                registered,
+                requested,
+            } => write!(
+                f,
+                "database {} was opened as {}, cannot open it as {}",
+                name.as_deref().unwrap_or("<main>"),
+                registered,
+                requested
+            ),
+            Error::CustomComparator { name } => write!(
+                f,
+                "database {} has a custom comparator, which typed databases do not support",
+                name.as_deref().unwrap_or("<main>")
+            ),
+            Error::AlreadyOpen(path) => write!(
+                f,
+                "environment {} is already open in this process",
+                path.display()
+            ),
+            Error::UniqueViolation { index, key } => write!(
+                f,
+                "unique index {} already holds key {:?}",
+                index.as_deref().unwrap_or("<main>"),
+                key
+            ),
+            Error::OutOfOrder { db, position, key } => write!(
+                f,
+                "record {} with key {:?} is out of order for database {}",
+                position,
+                key,
+                db.as_deref().unwrap_or("<main>")
+            ),
+            Error::DuplicateKey { db, key } => write!(
+                f,
+                "duplicate key {:?} for database {}",
+                key,
+                db.as_deref().unwrap_or("<main>")
+            ),
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Dump { line, message } => write!(f, "dump line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Lmdb { error, .. } => Some(error),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<lmdb_zero::Error> for Error {
+    fn from(err: lmdb_zero::Error) -> Self {
+        let context = Context::default();
+        match err {
+            lmdb_zero::Error::Code(error::NOTFOUND) => Error::NotFound(context),
+            lmdb_zero::Error::Code(error::MAP_FULL) => Error::MapFull(context),
+            error => Error::Lmdb { error, context },
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    #[inline]
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// Recovers the LMDB error behind `err`, or gives `err` back if it did not
+/// come from LMDB.
+impl TryFrom<Error> for lmdb_zero::Error {
+    type Error = Error;
+
+    fn try_from(err: Error) -> std::result::Result<Self, Error> {
+        match err {
+            Error::Lmdb { error, .. } => Ok(error),
+            Error::NotFound(_) => Ok(lmdb_zero::Error::Code(error::NOTFOUND)),
+            Error::MapFull(_) => Ok(lmdb_zero::Error::Code(error::MAP_FULL)),
+            err => Err(err),
+        }
+    }
+}
+
+/// Converts a `NotFound` error into `Ok(None)`.
+pub trait ResultExt<T> {
+    fn to_opt(self) -> Result<Option<T>>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    #[inline]
+    fn to_opt(self) -> Result<Option<T>> {
+        match self {
+            Ok(val) => Ok(Some(val)),
+            Err(Error::NotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+pub(crate) trait WithContext<T> {
+    fn context(self, op: &'static str, db: Option<&Arc<str>>) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> WithContext<T> for std::result::Result<T, E> {
+    #[inline]
+    fn context(self, op: &'static str, db: Option<&Arc<str>>) -> Result<T> {
+        self.map_err(|err| err.into().with_context(op, db))
+    }
+}
+
+/// Like `WithContext`, for the results of `Codec::encode`.
+pub(crate) trait EncodeContext<T> {
+    fn encode_context(self, op: &'static str, db: Option<&Arc<str>>) -> Result<T>;
+}
+
+impl<T> EncodeContext<T> for lmdb_zero::Result<T> {
+    #[inline]
+    fn encode_context(self, op: &'static str, db: Option<&Arc<str>>) -> Result<T> {
+        self.map_err(|err| Error::from_encode(err).with_context(op, db))
+    }
+}
+
+/// Like `WithContext`, for the results of `Codec::decode` and of reads that
+/// convert stored bytes.
+pub(crate) trait DecodeContext<T> {
+    fn decode_context(self, op: &'static str, db: Option<&Arc<str>>) -> Result<T>;
+}
+
+impl<T> DecodeContext<T> for lmdb_zero::Result<T> {
+    #[inline]
+    fn decode_context(self, op: &'static str, db: Option<&Arc<str>>) -> Result<T> {
+        self.map_err(|err| Error::from_decode(err).with_context(op, db))
+    }
+}
+
+/// Error types that may carry an LMDB error code, so that generic code can
+/// look for specific codes such as `MAP_FULL` in a caller's own error type.
+pub trait AsLmdbError {
+    fn lmdb_code(&self) -> Option<c_int>;
+}
+
+impl AsLmdbError for lmdb_zero::Error {
+    #[inline]
+    fn lmdb_code(&self) -> Option<c_int> {
+        match self {
+            lmdb_zero::Error::Code(code) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+impl AsLmdbError for Error {
+    #[inline]
+    fn lmdb_code(&self) -> Option<c_int> {
+        match self {
+            Error::Lmdb { error, .. } => error.lmdb_code(),
+            Error::NotFound(_) => Some(error::NOTFOUND),
+            Error::MapFull(_) => Some(error::MAP_FULL),
+            Error::UniqueViolation { .. }
+            | Error::OutOfOrder { .. }
+            | Error::DuplicateKey { .. } => Some(error::KEYEXIST),
+            _ => None,
+        }
+    }
+}
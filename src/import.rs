@@ -0,0 +1,350 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::vec;
+
+use lmdb_zero::traits::LmdbResultExt;
+
+use crate::compare::{Record, RecordOrder};
+use crate::error::{EncodeContext, Error, Result, WithContext};
+use crate::{BulkLoader, Codec, Database, Layout, ReadTransaction};
+
+/// Numbers the run files of every import in this process.
+static RUN_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// What an `Importer` does with records the database cannot both hold: those
+/// with equal keys in an `LmdbLayoutDefault` database, and equal key and
+/// value pairs in a dupsort one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Duplicates {
+    /// Fail with `Error::DuplicateKey`.
+    Reject,
+    /// Keep the record that came first in the input.
+    KeepFirst,
+    /// Keep the record that came last in the input.
+    KeepLast,
+}
+
+/// Imports unsorted records by external sorting: records are buffered up to
+/// `memory_budget` bytes, sorted and spilled to a run file in `temp_dir`, and
+/// the runs are then merged into a `BulkLoader`.
+///
+/// Run files are removed when the import finishes, whether or not it
+/// succeeds. As with `BulkLoader`, chunks committed before a failure remain,
+/// though records out of order with the existing contents are caught first.
+pub struct Importer<'a, 'e, K, V, L>
+where
+    K: ?Sized,
+    V: ?Sized,
+    L: Layout,
+{
+    db: &'a Database<'e, K, V, L>,
+    memory_budget: usize,
+    temp_dir: PathBuf,
+    duplicates: Duplicates,
+    chunk_size: usize,
+}
+
+impl<'a, 'e, K, V, L> Importer<'a, 'e, K, V, L>
+where
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+    L: Layout,
+{
+    #[inline]
+    pub fn new(db: &'a Database<'e, K, V, L>) -> Self {
+        Importer {
+            db,
+            memory_budget: 64 << 20,
+            temp_dir: std::env::temp_dir(),
+            duplicates: Duplicates::Reject,
+            chunk_size: 100_000,
+        }
+    }
+
+    /// Sets roughly how many bytes of records are held in memory before a
+    /// run is spilled.
+    #[inline]
+    pub fn memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = bytes;
+        self
+    }
+
+    #[inline]
+    pub fn temp_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.temp_dir = dir.as_ref().to_owned();
+        self
+    }
+
+    #[inline]
+    pub fn duplicates(mut self, duplicates: Duplicates) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Sets how many records are written per transaction, see
+    /// `BulkLoader::chunk_size`.
+    #[inline]
+    pub fn chunk_size(mut self, records: usize) -> Self {
+        self.chunk_size = records;
+        self
+    }
+
+    /// Writes `items` in any order, returning the number of records loaded.
+    ///
+    /// The records must sort after the existing contents of the database,
+    /// since they are appended with `MDB_APPEND`; there is no fallback to
+    /// plain puts. Once the input is sorted, its first record is compared
+    /// with the last one stored, and if it does not sort after it nothing is
+    /// written and this fails with `Error::OutOfOrder` at position 0. Records
+    /// that overlap the existing contents should be written with
+    /// `WriteAccessor::put` instead.
+    pub fn import<I, KI, VI>(&self, items: I) -> Result<u64>
+    where
+        I: IntoIterator<Item = (KI, VI)>,
+        KI: Borrow<K::Item>,
+        VI: Borrow<V::Item>,
+    {
+        let name = self.db.name_arc();
+        let (order, last) = {
+            let txn = ReadTransaction::new(self.db.env())?;
+            let order = RecordOrder::new(txn.db_flags(self.db)?);
+            let mut cursor = txn.cursor(self.db)?;
+            let access = txn.access();
+            let last = cursor
+                .0
+                .last::<[u8], [u8]>(access.as_lmdb())
+                .to_opt()
+                .context("import", name)?
+                .map(|(key, val)| (key.to_vec(), val.to_vec()));
+            (order, last)
+        };
+
+        let mut runs = Vec::new();
+        let mut buffer = Vec::new();
+        let mut used = 0;
+        for (key, val) in items {
+            let key = K::encode(key.borrow()).encode_context("import", name)?;
+            let val = V::encode(val.borrow()).encode_context("import", name)?;
+            used += key.len() + val.len() + 2 * size_of::<Vec<u8>>();
+            buffer.push((key.into_owned(), val.into_owned()));
+            if used >= self.memory_budget {
+                runs.push(self.spill(&order, &mut buffer)?);
+                used = 0;
+            }
+        }
+        buffer.sort_by(|a, b| order.cmp(a, b));
+        runs.push(Run::Memory(buffer.into_iter()));
+
+        let merged = Merge::new(runs, order, self.duplicates, self.db.name())?;
+        if let (Some(last), Some(first)) = (&last, merged.peek()) {
+            if order.cmp(last, first) != Ordering::Less {
+                return Err(Error::OutOfOrder {
+                    db: self.db.name().map(str::to_owned),
+                    position: 0,
+                    key: first.0.clone(),
+                });
+            }
+        }
+        BulkLoader::new(self.db)
+            .chunk_size(self.chunk_size)
+            .load_with(merged, |(key, val), append| append(key, val))
+    }
+
+    fn spill(&self, order: &RecordOrder, buffer: &mut Vec<Record>) -> Result<Run> {
+        buffer.sort_by(|a, b| order.cmp(a, b));
+        let id = RUN_FILES.fetch_add(1, AtomicOrdering::Relaxed);
+        let path = self
+            .temp_dir
+            .join(format!("lmdb-import-{}-{}.run", process::id(), id));
+        match write_run(&path, buffer.drain(..)) {
+            Ok(file) => Ok(Run::File(RunFile {
+                reader: BufReader::new(file),
+                path,
+            })),
+            Err(err) => {
+                let _ = fs::remove_file(&path);
+                Err(err.into())
+            }
+        }
+    }
+}
+
+/// Writes `records` to a new file at `path` and opens it for reading back.
+fn write_run<I: Iterator<Item = Record>>(path: &Path, records: I) -> io::Result<File> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for (key, val) in records {
+        writer.write_all(&(key.len() as u64).to_le_bytes())?;
+        writer.write_all(&(val.len() as u64).to_le_bytes())?;
+        writer.write_all(&key)?;
+        writer.write_all(&val)?;
+    }
+    writer.flush()?;
+    File::open(path)
+}
+
+/// A run file being read back, removed when dropped.
+struct RunFile {
+    reader: BufReader<File>,
+    path: PathBuf,
+}
+
+impl Drop for RunFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// A sorted run, either spilled to a file or the records left in memory at
+/// the end of the input.
+enum Run {
+    Memory(vec::IntoIter<Record>),
+    File(RunFile),
+}
+
+impl Run {
+    fn next(&mut self) -> Result<Option<Record>> {
+        let reader = match self {
+            Run::Memory(records) => return Ok(records.next()),
+            Run::File(file) => &mut file.reader,
+        };
+        // The run ends cleanly only between records; a partial header is
+        // left to `read_exact` to report as `UnexpectedEof`.
+        if reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        let mut lens = [0; 16];
+        reader.read_exact(&mut lens)?;
+        let (key_len, val_len) = lens.split_at(8);
+        let mut key = vec![0; u64::from_le_bytes(key_len.try_into().unwrap()) as usize];
+        let mut val = vec![0; u64::from_le_bytes(val_len.try_into().unwrap()) as usize];
+        reader.read_exact(&mut key)?;
+        reader.read_exact(&mut val)?;
+        Ok(Some((key, val)))
+    }
+}
+
+/// The head record of a run. `BinaryHeap` is a max-heap, so the ordering is
+/// reversed, with earlier runs first among equal records to keep input order.
+struct Head {
+    record: Record,
+    run: usize,
+    order: RecordOrder,
+}
+
+impl Ord for Head {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.order
+            .cmp(&self.record, &other.record)
+            .then(self.run.cmp(&other.run))
+            .reverse()
+    }
+}
+
+impl PartialOrd for Head {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Head {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Head {}
+
+/// Merges sorted runs into one sorted stream, resolving duplicates.
+struct Merge {
+    runs: Vec<Run>,
+    heap: BinaryHeap<Head>,
+    order: RecordOrder,
+    duplicates: Duplicates,
+    db: Option<String>,
+    pending: Option<Record>,
+}
+
+impl Merge {
+    fn new(
+        mut runs: Vec<Run>,
+        order: RecordOrder,
+        duplicates: Duplicates,
+        db: Option<&str>,
+    ) -> Result<Self> {
+        let mut heap = BinaryHeap::with_capacity(runs.len());
+        for (run, records) in runs.iter_mut().enumerate() {
+            if let Some(record) = records.next()? {
+                heap.push(Head { record, run, order });
+            }
+        }
+        Ok(Merge {
+            runs,
+            heap,
+            order,
+            duplicates,
+            db: db.map(str::to_owned),
+            pending: None,
+        })
+    }
+
+    /// The smallest record not yet returned, before resolving duplicates.
+    fn peek(&self) -> Option<&Record> {
+        match &self.pending {
+            Some(record) => Some(record),
+            None => self.heap.peek().map(|head| &head.record),
+        }
+    }
+
+    fn pop(&mut self) -> Result<Option<Record>> {
+        let Some(Head { record, run, order }) = self.heap.pop() else {
+            return Ok(None);
+        };
+        if let Some(next) = self.runs[run].next()? {
+            self.heap.push(Head {
+                record: next,
+                run,
+                order,
+            });
+        }
+        Ok(Some(record))
+    }
+}
+
+impl Iterator for Merge {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Result<Record>> {
+        loop {
+            let record = match self.pop() {
+                Ok(Some(record)) => record,
+                Ok(None) => return self.pending.take().map(Ok),
+                Err(err) => return Some(Err(err)),
+            };
+            let Some(prev) = self.pending.take() else {
+                self.pending = Some(record);
+                continue;
+            };
+            if self.order.cmp(&prev, &record) != Ordering::Equal {
+                self.pending = Some(record);
+                return Some(Ok(prev));
+            }
+            match self.duplicates {
+                Duplicates::Reject => {
+                    return Some(Err(Error::DuplicateKey {
+                        db: self.db.clone(),
+                        key: record.0,
+                    }))
+                }
+                Duplicates::KeepFirst => self.pending = Some(prev),
+                Duplicates::KeepLast => self.pending = Some(record),
+            }
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use std::borrow::Borrow;
+use std::ops::RangeBounds;
+use std::sync::Arc;
+
+use lmdb_zero::{db, error, put, MaybeOwned};
+
+use crate::error::{AsLmdbError, EncodeContext, Error, Result, ResultExt, WithContext};
+use crate::{
+    Codec, CodecOwned, ConstAccessor, ConstTransaction, Database, DupIter, Layout,
+    LmdbLayoutDefault, LmdbLayoutDupsort, RangeIter, WriteAccessor, WriteTransaction,
+};
+
+type Extractor<'e, V> = Box<dyn Fn(&<V as Codec>::Item) -> Result<Vec<u8>> + Send + Sync + 'e>;
+
+/// Keeps an index database in step with the primary one, working on encoded
+/// keys so that indexes of different key types can be stored together.
+trait Indexer<V: Codec + ?Sized>: Send + Sync {
+    fn index_key(&self, val: &V::Item) -> Result<Vec<u8>>;
+    fn check(&self, access: &WriteAccessor, index_key: &[u8], key: &[u8]) -> Result<()>;
+    fn insert(&self, access: &mut WriteAccessor, index_key: &[u8], key: &[u8]) -> Result<()>;
+    fn remove(&self, access: &mut WriteAccessor, index_key: &[u8], key: &[u8]) -> Result<()>;
+}
+
+/// A secondary index mapping a field extracted from each value of an
+/// `IndexedDatabase` back to the primary keys holding it.
+///
+/// With `LmdbLayoutDupsort` many records may share an index key; with
+/// `LmdbLayoutDefault` the index is unique.
+pub struct Index<'e, I, K, V, L = LmdbLayoutDupsort>
+where
+    I: Codec + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+    L: Layout,
+{
+    db: Database<'e, I, K, L>,
+    extract: Extractor<'e, V>,
+}
+
+impl<'e, I, K, V, L> Index<'e, I, K, V, L>
+where
+    I: Codec + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+    L: Layout,
+{
+    /// Iterates over the `(index key, primary key)` pairs whose index keys
+    /// fall within `range`.
+    #[inline]
+    pub fn range<'txn, 'access, 'db, R>(
+        &'db self,
+        txn: &'txn ConstTransaction<'e>,
+        access: &'access ConstAccessor<'txn>,
+        range: R,
+    ) -> Result<RangeIter<'access, 'access, 'txn, 'db, I, K, L>>
+    where
+        R: RangeBounds<I::Item>,
+    {
+        RangeIter::new(MaybeOwned::Owned(txn.cursor(&self.db)?), access, range)
+    }
+
+    #[inline]
+    pub fn as_database(&self) -> &Database<'e, I, K, L> {
+        &self.db
+    }
+}
+
+impl<'e, I, K, V> Index<'e, I, K, V, LmdbLayoutDupsort>
+where
+    I: Codec + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+{
+    /// Iterates over the primary keys whose values have `index_key`.
+    #[inline]
+    pub fn get<'txn, 'access, 'db>(
+        &'db self,
+        txn: &'txn ConstTransaction<'e>,
+        access: &'access ConstAccessor<'txn>,
+        index_key: &I::Item,
+    ) -> Result<DupIter<'access, 'access, 'txn, 'db, I, K, LmdbLayoutDupsort>> {
+        DupIter::new(MaybeOwned::Owned(txn.cursor(&self.db)?), access, index_key)
+    }
+}
+
+impl<'e, I, K, V> Index<'e, I, K, V, LmdbLayoutDefault>
+where
+    I: Codec + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+{
+    /// Returns the primary key of the record whose value has `index_key`.
+    #[inline]
+    pub fn get<'txn, 'access>(
+        &self,
+        access: &'access ConstAccessor<'txn>,
+        index_key: &I::Item,
+    ) -> Result<K::Decoded<'access>>
+    where
+        K: 'access,
+        'e: 'txn,
+    {
+        access.get(&self.db, index_key)
+    }
+}
+
+impl<'e, I, K, V, L> Index<'e, I, K, V, L>
+where
+    I: Codec + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+    L: Layout,
+{
+    #[inline]
+    fn is_unique(&self) -> bool {
+        !L::flags().contains(db::DUPSORT)
+    }
+
+    fn violation(&self, index_key: &[u8]) -> Error {
+        Error::UniqueViolation {
+            index: self.db.name().map(str::to_owned),
+            key: index_key.to_vec(),
+        }
+    }
+}
+
+impl<'e, I, K, V, L> Indexer<V> for Index<'e, I, K, V, L>
+where
+    I: Codec + ?Sized,
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+    L: Layout,
+    Database<'e, I, K, L>: Send + Sync,
+{
+    #[inline]
+    fn index_key(&self, val: &V::Item) -> Result<Vec<u8>> {
+        (self.extract)(val)
+    }
+
+    fn check(&self, access: &WriteAccessor, index_key: &[u8], key: &[u8]) -> Result<()> {
+        if !self.is_unique() {
+            return Ok(());
+        }
+        let owner = access
+            .as_lmdb()
+            .get::<[u8], [u8]>(&self.db.0, index_key)
+            .context("index check", self.db.name_arc())
+            .to_opt()?;
+        match owner {
+            Some(owner) if owner != key => Err(self.violation(index_key)),
+            _ => Ok(()),
+        }
+    }
+
+    fn insert(&self, access: &mut WriteAccessor, index_key: &[u8], key: &[u8]) -> Result<()> {
+        if !self.is_unique() {
+            return access
+                .as_lmdb_mut()
+                .put(&self.db.0, index_key, key, put::Flags::empty())
+                .context("index put", self.db.name_arc());
+        }
+        match access
+            .as_lmdb_mut()
+            .put(&self.db.0, index_key, key, put::NOOVERWRITE)
+            .context("index put", self.db.name_arc())
+        {
+            Err(err) if err.lmdb_code() == Some(error::KEYEXIST) => {
+                self.check(access, index_key, key)
+            }
+            result => result,
+        }
+    }
+
+    fn remove(&self, access: &mut WriteAccessor, index_key: &[u8], key: &[u8]) -> Result<()> {
+        access
+            .as_lmdb_mut()
+            .del_item(&self.db.0, index_key, key)
+            .context("index del", self.db.name_arc())
+            .to_opt()
+            .map(drop)
+    }
+}
+
+/// A primary database together with secondary indexes that are updated by
+/// every `put` and `del_key` made through it, within the same transaction.
+///
+/// Indexes only reflect writes made through the `IndexedDatabase`; writing to
+/// the primary database directly leaves them stale.
+pub struct IndexedDatabase<'e, K, V>
+where
+    K: Codec + ?Sized,
+    V: Codec + ?Sized,
+{
+    primary: Database<'e, K, V, LmdbLayoutDefault>,
+    indexes: Vec<Arc<dyn Indexer<V> + 'e>>,
+}
+
+impl<'e, K, V> IndexedDatabase<'e, K, V>
+where
+    K: Codec + ?Sized,
+    V: CodecOwned + ?Sized,
+    V::Owned: Borrow<V::Item>,
+{
+    #[inline]
+    pub fn new(primary: Database<'e, K, V, LmdbLayoutDefault>) -> Self {
+        IndexedDatabase {
+            primary,
+            indexes: Vec::new(),
+        }
+    }
+
+    /// Indexes the values by `extract` in `db`, which should be empty. The
+    /// returned handle is used for lookups.
+    #[inline]
+    pub fn add_index<I, T, F>(
+        &mut self,
+        db: Database<'e, I, K, LmdbLayoutDupsort>,
+        extract: F,
+    ) -> Arc<Index<'e, I, K, V>>
+    where
+        I: Codec + ?Sized + 'e,
+        K: 'e,
+        V: 'e,
+        T: Borrow<I::Item>,
+        F: Fn(&V::Item) -> T + Send + Sync + 'e,
+        Database<'e, I, K, LmdbLayoutDupsort>: Send + Sync,
+    {
+        self.push_index(db, extract)
+    }
+
+    /// Like `add_index`, but writes that would give two records the same
+    /// index key fail with `Error::UniqueViolation`.
+    #[inline]
+    pub fn add_unique_index<I, T, F>(
+        &mut self,
+        db: Database<'e, I, K, LmdbLayoutDefault>,
+        extract: F,
+    ) -> Arc<Index<'e, I, K, V, LmdbLayoutDefault>>
+    where
+        I: Codec + ?Sized + 'e,
+        K: 'e,
+        V: 'e,
+        T: Borrow<I::Item>,
+        F: Fn(&V::Item) -> T + Send + Sync + 'e,
+        Database<'e, I, K, LmdbLayoutDefault>: Send + Sync,
+    {
+        self.push_index(db, extract)
+    }
+
+    fn push_index<I, T, F, L>(
+        &mut self,
+        db: Database<'e, I, K, L>,
+        extract: F,
+    ) -> Arc<Index<'e, I, K, V, L>>
+    where
+        I: Codec + ?Sized + 'e,
+        K: 'e,
+        V: 'e,
+        L: Layout + 'e,
+        T: Borrow<I::Item>,
+        F: Fn(&V::Item) -> T + Send + Sync + 'e,
+        Database<'e, I, K, L>: Send + Sync,
+    {
+        let index = Arc::new(Index {
+            db,
+            extract: Box::new(move |val| {
+                let index_key = extract(val);
+                Ok(I::encode(index_key.borrow())
+                    .map_err(Error::from_encode)?
+                    .into_owned())
+            }),
+        });
+        self.indexes.push(index.clone());
+        index
+    }
+
+    #[inline]
+    pub fn get<'txn, 'access>(
+        &self,
+        access: &'access ConstAccessor<'txn>,
+        key: &K::Item,
+    ) -> Result<V::Decoded<'access>>
+    where
+        V: 'access,
+        'e: 'txn,
+    {
+        access.get(&self.primary, key)
+    }
+
+    /// Stores `val` under `key`, replacing the index entries of any previous
+    /// value.
+    ///
+    /// Unique indexes are checked before anything is written, so a
+    /// `UniqueViolation` leaves the transaction unchanged; other failures may
+    /// leave it partially updated, see `put_atomic`.
+    pub fn put(
+        &self,
+        access: &mut WriteAccessor,
+        key: &K::Item,
+        val: &V::Item,
+        flags: put::Flags,
+    ) -> Result<()> {
+        let encoded = K::encode(key).encode_context("put", self.primary.name_arc())?;
+        let old = self.get_owned(access, key)?;
+        let mut updates = Vec::with_capacity(self.indexes.len());
+        for index in &self.indexes {
+            let new_key = index.index_key(val)?;
+            let old_key = match &old {
+                Some(old) => Some(index.index_key(old.borrow())?),
+                None => None,
+            };
+            if old_key.as_ref() != Some(&new_key) {
+                index.check(access, &new_key, &encoded)?;
+                updates.push((index, old_key, new_key));
+            }
+        }
+
+        access.put(&self.primary, key, val, flags)?;
+        for (index, old_key, new_key) in updates {
+            if let Some(old_key) = old_key {
+                index.remove(access, &old_key, &encoded)?;
+            }
+            index.insert(access, &new_key, &encoded)?;
+        }
+        Ok(())
+    }
+
+    /// Like `put`, but runs in a child transaction so that a failure part
+    /// way through leaves no partial writes behind.
+    pub fn put_atomic(
+        &self,
+        txn: &mut WriteTransaction,
+        key: &K::Item,
+        val: &V::Item,
+        flags: put::Flags,
+    ) -> Result<()> {
+        let child = txn.child_tx()?;
+        self.put(&mut child.access(), key, val, flags)?;
+        child.commit()
+    }
+
+    /// Deletes `key` and its index entries, failing with `Error::NotFound` if
+    /// it is not present.
+    pub fn del_key(&self, access: &mut WriteAccessor, key: &K::Item) -> Result<()> {
+        let old = self.get_owned(access, key)?;
+        access.del_key(&self.primary, key)?;
+
+        if let Some(old) = old {
+            let key = K::encode(key).encode_context("del_key", self.primary.name_arc())?;
+            for index in &self.indexes {
+                let old_key = index.index_key(old.borrow())?;
+                index.remove(access, &old_key, &key)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn primary(&self) -> &Database<'e, K, V, LmdbLayoutDefault> {
+        &self.primary
+    }
+
+    fn get_owned(&self, access: &WriteAccessor, key: &K::Item) -> Result<Option<V::Owned>> {
+        Ok(access.get_opt(&self.primary, key)?.map(V::into_owned))
+    }
+}
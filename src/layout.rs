@@ -1,8 +1,14 @@
+use lmdb_zero::db;
+
 pub struct LmdbLayoutDefault;
 pub struct LmdbLayoutDupsort;
 pub struct LmdbLayoutDupfixed;
 
-pub trait Layout {}
+pub trait Layout {
+    /// The `DUPSORT`/`DUPFIXED` flags a database must have to be used with
+    /// this layout.
+    fn flags() -> db::Flags;
+}
 pub trait LayoutNoDuplicates {}
 pub trait LayoutDupsort {}
 pub trait LayoutDupfixed {}
@@ -10,7 +16,25 @@ pub trait LayoutDupfixed {}
 impl LayoutDupsort for LmdbLayoutDupsort {}
 impl LayoutDupsort for LmdbLayoutDupfixed {}
 impl LayoutDupfixed for LmdbLayoutDupfixed {}
-impl Layout for LmdbLayoutDupsort {}
-impl Layout for LmdbLayoutDupfixed {}
-impl Layout for LmdbLayoutDefault {}
 impl LayoutNoDuplicates for LmdbLayoutDefault {}
+
+impl Layout for LmdbLayoutDupsort {
+    #[inline]
+    fn flags() -> db::Flags {
+        db::DUPSORT
+    }
+}
+
+impl Layout for LmdbLayoutDupfixed {
+    #[inline]
+    fn flags() -> db::Flags {
+        db::DUPSORT | db::DUPFIXED
+    }
+}
+
+impl Layout for LmdbLayoutDefault {
+    #[inline]
+    fn flags() -> db::Flags {
+        db::Flags::empty()
+    }
+}
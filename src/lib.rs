@@ -1,48 +1,44 @@
 pub mod accessor;
+pub mod bulk;
+pub mod codec;
+mod compare;
 pub mod cursor;
 pub mod cursor_iter;
 pub mod database;
+pub mod dump;
+pub mod environment;
+pub mod error;
+pub mod import;
+pub mod index;
 pub mod layout;
+pub mod ordered;
+pub mod sequence;
+#[cfg(feature = "serde")]
+pub mod serde_codec;
 pub mod traits;
 pub mod transaction;
 
 pub use crate::cursor::*;
 pub use accessor::*;
+pub use bulk::BulkLoader;
+pub use codec::*;
 pub use cursor_iter::*;
 pub use database::*;
+pub use dump::DumpFormat;
+pub use environment::*;
+pub use error::{AsLmdbError, Context, Error, ResultExt};
+pub use import::{Duplicates, Importer};
+pub use index::*;
 pub use layout::*;
+pub use ordered::*;
+pub use sequence::*;
+#[cfg(feature = "serde")]
+pub use serde_codec::*;
 pub use transaction::*;
 
-#[macro_export]
-macro_rules! cursor {
-    ($name:ident, $cursor:ident, $db:ident, $desc:expr) => {
-        pub fn $name<'txn, 'db>(
-            &'db self,
-            txn: &'txn ConstTransaction<'e>,
-        ) -> Result<$cursor<'txn, 'db>>
-        where
-            'e: 'db,
-        {
-            txn.cursor(&self.$db).context($desc)
-        }
-    };
-}
-
-#[macro_export]
-macro_rules! types {
-    ($cursor:ident, $db:ident, $key:ty, $value:ty, $layout:ty) => {
-        pub type $cursor<'t, 'd> = Cursor<'t, 'd, $key, $value, $layout>;
-        pub type $db<'e> = Database<'e, $key, $value, $layout>;
-    };
-}
+pub use lmdb_zero_typed_derive::Schema;
 
-#[macro_export]
-macro_rules! db {
-    ($env:expr, $desc: expr, $options:expr) => {
-        Arc::new(Database::open(
-            $env.clone(),
-            Some($desc),
-            &DatabaseOptions::new(db::CREATE | $options),
-        )?)
-    };
+#[doc(hidden)]
+pub mod __private {
+    pub use lmdb_zero;
 }
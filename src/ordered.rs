@@ -0,0 +1,258 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use lmdb_zero::{Error, Result};
+
+use crate::{Codec, CodecOwned, Prefix};
+
+/// A type with a byte encoding whose lexicographic order matches `Ord` (or,
+/// for floats, `total_cmp`).
+///
+/// Encodings are self-delimiting so that they can be concatenated to form
+/// tuple keys: integers and floats are fixed width, strings and byte strings
+/// escape `0x00` as `0x00 0xff` and end with `0x00 0x00`.
+pub trait OrderedKey: Sized {
+    fn write_key(&self, out: &mut Vec<u8>);
+    fn read_key(input: &mut &[u8]) -> Result<Self>;
+}
+
+/// Codec storing `T` in its order-preserving encoding, so that LMDB's bytewise
+/// key comparison sorts entries by `T`.
+pub struct Ordered<T>(PhantomData<fn() -> T>);
+
+impl<T: OrderedKey> Codec for Ordered<T> {
+    type Item = T;
+    type Decoded<'a>
+        = T
+    where
+        Self: 'a;
+
+    fn encode(item: &T) -> Result<Cow<'_, [u8]>> {
+        let mut out = Vec::new();
+        item.write_key(&mut out);
+        Ok(Cow::Owned(out))
+    }
+
+    fn decode(mut bytes: &[u8]) -> Result<T> {
+        let item = T::read_key(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(Error::ValRejected(format!(
+                "{} trailing bytes after ordered key",
+                bytes.len()
+            )));
+        }
+        Ok(item)
+    }
+}
+
+impl<T: OrderedKey> CodecOwned for Ordered<T> {
+    type Owned = T;
+
+    #[inline]
+    fn into_owned(decoded: T) -> T {
+        decoded
+    }
+}
+
+fn take<'a>(input: &mut &'a [u8], n: usize) -> Result<&'a [u8]> {
+    if input.len() < n {
+        return Err(Error::ValRejected(format!(
+            "ordered key truncated: need {} bytes, have {}",
+            n,
+            input.len()
+        )));
+    }
+    let (head, tail) = input.split_at(n);
+    *input = tail;
+    Ok(head)
+}
+
+macro_rules! ordered_unsigned {
+    ($($t:ty),*) => {$(
+        impl OrderedKey for $t {
+            fn write_key(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_be_bytes());
+            }
+
+            fn read_key(input: &mut &[u8]) -> Result<Self> {
+                let bytes = take(input, std::mem::size_of::<$t>())?;
+                Ok(<$t>::from_be_bytes(bytes.try_into().unwrap()))
+            }
+        }
+    )*};
+}
+
+macro_rules! ordered_signed {
+    ($($t:ty => $u:ty),*) => {$(
+        impl OrderedKey for $t {
+            fn write_key(&self, out: &mut Vec<u8>) {
+                (*self as $u ^ (1 << (<$u>::BITS - 1))).write_key(out);
+            }
+
+            fn read_key(input: &mut &[u8]) -> Result<Self> {
+                Ok((<$u>::read_key(input)? ^ (1 << (<$u>::BITS - 1))) as $t)
+            }
+        }
+    )*};
+}
+
+macro_rules! ordered_float {
+    ($($t:ty => $u:ty),*) => {$(
+        impl OrderedKey for $t {
+            fn write_key(&self, out: &mut Vec<u8>) {
+                let bits = self.to_bits();
+                let sign = 1 << (<$u>::BITS - 1);
+                let bits = if bits & sign != 0 { !bits } else { bits ^ sign };
+                bits.write_key(out);
+            }
+
+            fn read_key(input: &mut &[u8]) -> Result<Self> {
+                let bits = <$u>::read_key(input)?;
+                let sign = 1 << (<$u>::BITS - 1);
+                let bits = if bits & sign != 0 { bits ^ sign } else { !bits };
+                Ok(<$t>::from_bits(bits))
+            }
+        }
+    )*};
+}
+
+ordered_unsigned!(u8, u16, u32, u64, u128);
+ordered_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128);
+ordered_float!(f32 => u32, f64 => u64);
+
+impl OrderedKey for bool {
+    fn write_key(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn read_key(input: &mut &[u8]) -> Result<Self> {
+        match u8::read_key(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            b => Err(Error::ValRejected(format!("invalid ordered bool {}", b))),
+        }
+    }
+}
+
+fn write_escaped(bytes: &[u8], out: &mut Vec<u8>) {
+    write_escaped_prefix(bytes, out);
+    out.extend_from_slice(&[0, 0]);
+}
+
+fn write_escaped_prefix(bytes: &[u8], out: &mut Vec<u8>) {
+    for &b in bytes {
+        out.push(b);
+        if b == 0 {
+            out.push(0xff);
+        }
+    }
+}
+
+fn read_escaped(input: &mut &[u8]) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    loop {
+        match take(input, 1)?[0] {
+            0 => match take(input, 1)?[0] {
+                0 => return Ok(bytes),
+                0xff => bytes.push(0),
+                b => {
+                    return Err(Error::ValRejected(format!(
+                        "invalid escape 0x00 0x{:02x} in ordered key",
+                        b
+                    )))
+                }
+            },
+            b => bytes.push(b),
+        }
+    }
+}
+
+impl OrderedKey for Vec<u8> {
+    fn write_key(&self, out: &mut Vec<u8>) {
+        write_escaped(self, out);
+    }
+
+    fn read_key(input: &mut &[u8]) -> Result<Self> {
+        read_escaped(input)
+    }
+}
+
+impl OrderedKey for String {
+    fn write_key(&self, out: &mut Vec<u8>) {
+        write_escaped(self.as_bytes(), out);
+    }
+
+    fn read_key(input: &mut &[u8]) -> Result<Self> {
+        String::from_utf8(read_escaped(input)?).map_err(|e| Error::ValRejected(e.to_string()))
+    }
+}
+
+impl Prefix<str> for Ordered<String> {
+    fn encode_prefix(prefix: &str) -> Result<Cow<'_, [u8]>> {
+        let mut out = Vec::new();
+        write_escaped_prefix(prefix.as_bytes(), &mut out);
+        Ok(Cow::Owned(out))
+    }
+}
+
+impl Prefix<[u8]> for Ordered<Vec<u8>> {
+    fn encode_prefix(prefix: &[u8]) -> Result<Cow<'_, [u8]>> {
+        let mut out = Vec::new();
+        write_escaped_prefix(prefix, &mut out);
+        Ok(Cow::Owned(out))
+    }
+}
+
+macro_rules! ordered_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: OrderedKey),+> OrderedKey for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn write_key(&self, out: &mut Vec<u8>) {
+                let ($($name,)+) = self;
+                $($name.write_key(out);)+
+            }
+
+            fn read_key(input: &mut &[u8]) -> Result<Self> {
+                Ok(($($name::read_key(input)?,)+))
+            }
+        }
+    };
+}
+
+ordered_tuple!(A);
+ordered_tuple!(A, B);
+ordered_tuple!(A, B, C);
+ordered_tuple!(A, B, C, D);
+ordered_tuple!(A, B, C, D, E);
+ordered_tuple!(A, B, C, D, E, F);
+
+// A tuple key can be scanned by any tuple of its leading elements.
+macro_rules! ordered_tuple_prefix {
+    ($($name:ident),+; $($rest:ident),+) => {
+        impl<$($name: OrderedKey,)+ $($rest: OrderedKey),+> Prefix<($($name,)+)>
+            for Ordered<($($name,)+ $($rest,)+)>
+        {
+            fn encode_prefix(prefix: &($($name,)+)) -> Result<Cow<'_, [u8]>> {
+                let mut out = Vec::new();
+                prefix.write_key(&mut out);
+                Ok(Cow::Owned(out))
+            }
+        }
+    };
+}
+
+ordered_tuple_prefix!(A; B);
+ordered_tuple_prefix!(A; B, C);
+ordered_tuple_prefix!(A, B; C);
+ordered_tuple_prefix!(A; B, C, D);
+ordered_tuple_prefix!(A, B; C, D);
+ordered_tuple_prefix!(A, B, C; D);
+ordered_tuple_prefix!(A; B, C, D, E);
+ordered_tuple_prefix!(A, B; C, D, E);
+ordered_tuple_prefix!(A, B, C; D, E);
+ordered_tuple_prefix!(A, B, C, D; E);
+ordered_tuple_prefix!(A; B, C, D, E, F);
+ordered_tuple_prefix!(A, B; C, D, E, F);
+ordered_tuple_prefix!(A, B, C; D, E, F);
+ordered_tuple_prefix!(A, B, C, D; E, F);
+ordered_tuple_prefix!(A, B, C, D, E; F);
@@ -0,0 +1,73 @@
+use std::ops::Range;
+
+use lmdb_zero::put;
+
+use crate::error::{Context, Error, Result, ResultExt, WithContext};
+use crate::{ConstAccessor, Database, LmdbLayoutDefault, Ordered, WriteAccessor};
+
+/// A named counter handing out increasing `u64`s, stored in a metadata
+/// database alongside the data it numbers.
+///
+/// The counter is updated through the caller's `WriteAccessor`, so values
+/// are only used up if that transaction commits; an aborted transaction hands
+/// the same values out again.
+pub struct Sequence<'a, 'e> {
+    db: &'a Database<'e, str, Ordered<u64>, LmdbLayoutDefault>,
+    name: String,
+}
+
+impl<'a, 'e> Sequence<'a, 'e> {
+    #[inline]
+    pub fn new<N: Into<String>>(
+        db: &'a Database<'e, str, Ordered<u64>, LmdbLayoutDefault>,
+        name: N,
+    ) -> Self {
+        Sequence {
+            db,
+            name: name.into(),
+        }
+    }
+
+    /// The value the next call to `next` will return; sequences start at 0.
+    #[inline]
+    pub fn peek<'txn>(&self, access: &ConstAccessor<'txn>) -> Result<u64>
+    where
+        'e: 'txn,
+    {
+        Ok(access.get_opt(self.db, &self.name)?.unwrap_or(0))
+    }
+
+    #[inline]
+    pub fn next<'txn>(&self, access: &mut WriteAccessor<'txn>) -> Result<u64>
+    where
+        'e: 'txn,
+    {
+        self.next_n(access, 1).map(|ids| ids.start)
+    }
+
+    /// Reserves `n` consecutive values, failing with `Error::Overflow` if
+    /// the sequence would pass `u64::MAX`.
+    pub fn next_n<'txn>(&self, access: &mut WriteAccessor<'txn>, n: u64) -> Result<Range<u64>>
+    where
+        'e: 'txn,
+    {
+        let start = self.peek(access)?;
+        let end = start
+            .checked_add(n)
+            .ok_or(Error::Overflow(Context::default()))
+            .context("next_n", self.db.name_arc())?;
+        access.put(self.db, &self.name, &end, put::Flags::empty())?;
+        Ok(start..end)
+    }
+
+    /// Removes the sequence, so that it starts again at 0.
+    #[inline]
+    pub fn reset(&self, access: &mut WriteAccessor) -> Result<()> {
+        access.del_key(self.db, &self.name).to_opt().map(drop)
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
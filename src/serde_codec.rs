@@ -0,0 +1,124 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use lmdb_zero::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{Codec, CodecOwned};
+
+/// A wire format usable by the `Serde` codec.
+pub trait SerdeFormat {
+    fn serialize<T: Serialize + ?Sized>(item: &T) -> Result<Vec<u8>>;
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T>;
+}
+
+/// Stores any `T: Serialize + DeserializeOwned` using the format `F`, e.g.
+/// `Database<str, Serde<Bincode, Config>, LmdbLayoutDefault>`.
+///
+/// Values are decoded into an owned `T`. Serialization failures are reported
+/// as `Error::Encode` and deserialization failures as `Error::Decode`.
+pub struct Serde<F, T>(PhantomData<(F, fn() -> T)>);
+
+impl<F, T> Codec for Serde<F, T>
+where
+    F: SerdeFormat,
+    T: Serialize + DeserializeOwned,
+{
+    type Item = T;
+    type Decoded<'a>
+        = T
+    where
+        Self: 'a;
+
+    #[inline]
+    fn encode(item: &T) -> Result<Cow<'_, [u8]>> {
+        F::serialize(item).map(Cow::Owned)
+    }
+
+    #[inline]
+    fn decode(bytes: &[u8]) -> Result<T> {
+        F::deserialize(bytes)
+    }
+}
+
+impl<F, T> CodecOwned for Serde<F, T>
+where
+    F: SerdeFormat,
+    T: Serialize + DeserializeOwned,
+{
+    type Owned = T;
+
+    #[inline]
+    fn into_owned(decoded: T) -> T {
+        decoded
+    }
+}
+
+#[cfg(any(
+    feature = "bincode",
+    feature = "postcard",
+    feature = "json",
+    feature = "cbor"
+))]
+fn rejected<E: std::fmt::Display>(e: E) -> lmdb_zero::Error {
+    lmdb_zero::Error::ValRejected(e.to_string())
+}
+
+#[cfg(feature = "bincode")]
+pub struct Bincode;
+
+#[cfg(feature = "bincode")]
+impl SerdeFormat for Bincode {
+    fn serialize<T: Serialize + ?Sized>(item: &T) -> Result<Vec<u8>> {
+        bincode::serialize(item).map_err(rejected)
+    }
+
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+        bincode::deserialize(bytes).map_err(rejected)
+    }
+}
+
+#[cfg(feature = "postcard")]
+pub struct Postcard;
+
+#[cfg(feature = "postcard")]
+impl SerdeFormat for Postcard {
+    fn serialize<T: Serialize + ?Sized>(item: &T) -> Result<Vec<u8>> {
+        postcard::to_allocvec(item).map_err(rejected)
+    }
+
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+        postcard::from_bytes(bytes).map_err(rejected)
+    }
+}
+
+#[cfg(feature = "json")]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl SerdeFormat for Json {
+    fn serialize<T: Serialize + ?Sized>(item: &T) -> Result<Vec<u8>> {
+        serde_json::to_vec(item).map_err(rejected)
+    }
+
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+        serde_json::from_slice(bytes).map_err(rejected)
+    }
+}
+
+#[cfg(feature = "cbor")]
+pub struct Cbor;
+
+#[cfg(feature = "cbor")]
+impl SerdeFormat for Cbor {
+    fn serialize<T: Serialize + ?Sized>(item: &T) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(item, &mut bytes).map_err(rejected)?;
+        Ok(bytes)
+    }
+
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+        ciborium::from_reader(bytes).map_err(rejected)
+    }
+}
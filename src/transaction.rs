@@ -1,7 +1,14 @@
-use lmdb_zero::{Environment, Result};
+use std::iter::Rev;
+use std::ops::RangeBounds;
+
+use lmdb_zero::{Environment, MaybeOwned};
 use supercow::NonSyncSupercow;
 
-use crate::{ConstAccessor, Cursor, Database, Layout, StaleCursor, WriteAccessor};
+use crate::compare::RecordOrder;
+use crate::error::{Result, WithContext};
+use crate::{
+    Codec, ConstAccessor, Cursor, Database, DoubleEndedIter, Layout, StaleCursor, WriteAccessor,
+};
 
 #[derive(Debug)]
 pub struct ResetTransaction<'env>(pub lmdb_zero::ResetTransaction<'env>);
@@ -30,11 +37,90 @@ impl<'env> ConstTransaction<'env> {
         L: Layout,
         'env: 'db,
     {
-        match self {
-            ConstTransaction::Read(txn) => txn.cursor(&db.0),
-            ConstTransaction::Write(txn) => txn.cursor(&db.0),
-        }
-        .map(Cursor::from_lmdb)
+        let order = RecordOrder::new(self.db_flags(db)?);
+        self.as_lmdb()
+            .cursor(&db.0)
+            .map(|cursor| {
+                Cursor::from_lmdb(cursor)
+                    .with_db_name(db.name_arc())
+                    .with_order(order)
+            })
+            .context("cursor", db.name_arc())
+    }
+
+    /// Iterates over every entry of `db`, from either end.
+    #[inline]
+    pub fn iter<'txn, 'access, 'db, K, V, L>(
+        &'txn self,
+        db: &'db Database<'env, K, V, L>,
+        access: &'access ConstAccessor<'txn>,
+    ) -> Result<DoubleEndedIter<'access, 'access, 'txn, 'db, K, V, L>>
+    where
+        K: Codec + 'db + ?Sized,
+        V: Codec + 'db + ?Sized,
+        L: Layout,
+        'env: 'db,
+    {
+        self.range(db, access, ..)
+    }
+
+    /// Iterates over the entries of `db` whose keys fall within `range`, from
+    /// either end.
+    #[inline]
+    pub fn range<'txn, 'access, 'db, K, V, L, R>(
+        &'txn self,
+        db: &'db Database<'env, K, V, L>,
+        access: &'access ConstAccessor<'txn>,
+        range: R,
+    ) -> Result<DoubleEndedIter<'access, 'access, 'txn, 'db, K, V, L>>
+    where
+        K: Codec + 'db + ?Sized,
+        V: Codec + 'db + ?Sized,
+        L: Layout,
+        R: RangeBounds<K::Item>,
+        'env: 'db,
+    {
+        DoubleEndedIter::new(
+            MaybeOwned::Owned(self.cursor(db)?),
+            MaybeOwned::Owned(self.cursor(db)?),
+            access,
+            range,
+        )
+    }
+
+    /// Iterates over every entry of `db`, last to first.
+    #[inline]
+    pub fn iter_rev<'txn, 'access, 'db, K, V, L>(
+        &'txn self,
+        db: &'db Database<'env, K, V, L>,
+        access: &'access ConstAccessor<'txn>,
+    ) -> Result<Rev<DoubleEndedIter<'access, 'access, 'txn, 'db, K, V, L>>>
+    where
+        K: Codec + 'db + ?Sized,
+        V: Codec + 'db + ?Sized,
+        L: Layout,
+        'env: 'db,
+    {
+        self.iter(db, access).map(Iterator::rev)
+    }
+
+    /// Iterates over the entries of `db` whose keys fall within `range`, last
+    /// to first.
+    #[inline]
+    pub fn range_rev<'txn, 'access, 'db, K, V, L, R>(
+        &'txn self,
+        db: &'db Database<'env, K, V, L>,
+        access: &'access ConstAccessor<'txn>,
+        range: R,
+    ) -> Result<Rev<DoubleEndedIter<'access, 'access, 'txn, 'db, K, V, L>>>
+    where
+        K: Codec + 'db + ?Sized,
+        V: Codec + 'db + ?Sized,
+        L: Layout,
+        R: RangeBounds<K::Item>,
+        'env: 'db,
+    {
+        self.range(db, access, range).map(Iterator::rev)
     }
 
     #[inline]
@@ -54,15 +140,19 @@ impl<'env> ConstTransaction<'env> {
     }
 
     #[inline]
-    pub fn db_stat<K, V, L: Layout>(&self, db: &Database<K, V, L>) -> Result<lmdb_zero::Stat> {
+    pub fn db_stat<K: ?Sized, V: ?Sized, L: Layout>(
+        &self,
+        db: &Database<K, V, L>,
+    ) -> Result<lmdb_zero::Stat> {
         match self {
             ConstTransaction::Read(txn) => txn.db_stat(&db.0),
             ConstTransaction::Write(txn) => txn.db_stat(&db.0),
         }
+        .context("db_stat", db.name_arc())
     }
 
     #[inline]
-    pub fn db_flags<K, V, L: Layout>(
+    pub fn db_flags<K: ?Sized, V: ?Sized, L: Layout>(
         &self,
         db: &Database<K, V, L>,
     ) -> Result<lmdb_zero::db::Flags> {
@@ -70,13 +160,14 @@ impl<'env> ConstTransaction<'env> {
             ConstTransaction::Read(txn) => txn.db_flags(&db.0),
             ConstTransaction::Write(txn) => txn.db_flags(&db.0),
         }
+        .context("db_flags", db.name_arc())
     }
 
     #[inline]
     pub fn as_lmdb(&self) -> &lmdb_zero::ConstTransaction<'env> {
         match &self {
-            ConstTransaction::Write(txn) => &*txn,
-            ConstTransaction::Read(txn) => &*txn,
+            ConstTransaction::Write(txn) => txn,
+            ConstTransaction::Read(txn) => txn,
         }
     }
 }
@@ -92,11 +183,13 @@ impl<'env> ReadTransaction<'env> {
     where
         E: Into<NonSyncSupercow<'env, Environment>>,
     {
-        lmdb_zero::ReadTransaction::new(env).map(Self::from_lmdb)
+        lmdb_zero::ReadTransaction::new(env)
+            .map(Self::from_lmdb)
+            .context("begin read transaction", None)
     }
 
     #[inline]
-    pub fn access(&self) -> ConstAccessor {
+    pub fn access(&self) -> ConstAccessor<'_> {
         self.0.access()
     }
 
@@ -111,7 +204,7 @@ impl<'env> ReadTransaction<'env> {
         L: Layout,
         'env: 'db,
     {
-        self.as_lmdb().cursor(&db.0).map(Cursor::from_lmdb)
+        self.0.cursor(db)
     }
 
     #[inline]
@@ -122,9 +215,15 @@ impl<'env> ReadTransaction<'env> {
     where
         'env: 'db,
     {
+        let (name, order) = (cursor.4, cursor.5);
         self.as_lmdb()
             .dissoc_cursor(cursor.0)
-            .map(StaleCursor::from_lmdb)
+            .map(|cursor| {
+                StaleCursor::from_lmdb(cursor)
+                    .with_db_name(name.as_ref())
+                    .with_order(order)
+            })
+            .context("dissoc_cursor", name.as_ref())
     }
 
     #[inline]
@@ -132,7 +231,15 @@ impl<'env> ReadTransaction<'env> {
         &'txn self,
         cursor: StaleCursor<'db, K, V, L>,
     ) -> Result<Cursor<'txn, 'db, K, V, L>> {
-        self.as_lmdb().assoc_cursor(cursor.0).map(Cursor::from_lmdb)
+        let (name, order) = (cursor.4, cursor.5);
+        self.as_lmdb()
+            .assoc_cursor(cursor.0)
+            .map(|cursor| {
+                Cursor::from_lmdb(cursor)
+                    .with_db_name(name.as_ref())
+                    .with_order(order)
+            })
+            .context("assoc_cursor", name.as_ref())
     }
 
     #[inline]
@@ -173,7 +280,10 @@ impl<'env> ResetTransaction<'env> {
 
     #[inline]
     pub fn renew(self) -> Result<ReadTransaction<'env>> {
-        self.0.renew().map(ReadTransaction::from_lmdb)
+        self.0
+            .renew()
+            .map(ReadTransaction::from_lmdb)
+            .context("renew", None)
     }
 
     #[inline]
@@ -203,11 +313,13 @@ impl<'env> WriteTransaction<'env> {
     where
         E: Into<NonSyncSupercow<'env, Environment>>,
     {
-        lmdb_zero::WriteTransaction::new(env).map(WriteTransaction::from_lmdb)
+        lmdb_zero::WriteTransaction::new(env)
+            .map(WriteTransaction::from_lmdb)
+            .context("begin write transaction", None)
     }
 
     #[inline]
-    pub fn access(&self) -> WriteAccessor {
+    pub fn access(&self) -> WriteAccessor<'_> {
         WriteAccessor::from_lmdb(self.as_lmdb().access())
     }
 
@@ -222,7 +334,7 @@ impl<'env> WriteTransaction<'env> {
         L: Layout,
         'env: 'db,
     {
-        self.as_lmdb().cursor(&db.0).map(Cursor::from_lmdb)
+        self.0.cursor(db)
     }
 
     #[inline]
@@ -233,11 +345,12 @@ impl<'env> WriteTransaction<'env> {
         self.as_lmdb_mut()
             .child_tx()
             .map(WriteTransaction::from_lmdb)
+            .context("child_tx", None)
     }
 
     #[inline]
     pub fn commit(self) -> Result<()> {
-        self.into_lmdb().commit()
+        self.into_lmdb().commit().context("commit", None)
     }
 
     #[inline]
@@ -190,3 +190,243 @@ fn test_repr_from_as_raw() {
     assert_eq!(access.get(&db4, r!(2)).unwrap(), r!(2));
     assert_eq!(access.get(&db4, r!(3)).unwrap(), r!(3));
 }
+
+#[test]
+fn test_read_modify_write() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let names = env
+        .open_db::<str, str, LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+    let counts = env
+        .open_db::<str, u64, LmdbLayoutDefault>(Some("tree2"), &opts)
+        .unwrap();
+
+    env.write(|_, access| -> Result<(), Error> {
+        let new = access.update(&names, "a", |old| {
+            assert!(old.is_none());
+            Some("one".to_owned())
+        })?;
+        assert_eq!(new.as_deref(), Some("one"));
+
+        let new = access.upsert(&names, "a", |old| format!("{}!", old.unwrap()))?;
+        assert_eq!(new, "one!");
+        assert_eq!(access.get(&names, "a")?, "one!");
+
+        // Returning `None` deletes, and is a no-op for a missing key.
+        assert!(access.update(&names, "a", |_| None)?.is_none());
+        assert!(access.get_opt(&names, "a")?.is_none());
+        assert!(access.update(&names, "b", |_| None)?.is_none());
+
+        assert_eq!(access.fetch_add(&counts, "hits", 5)?, 0);
+        assert_eq!(access.fetch_add(&counts, "hits", 2)?, 5);
+        assert_eq!(access.fetch_add(&counts, "misses", 1)?, 0);
+        Ok(())
+    })
+    .unwrap();
+
+    // Stored integers need not be aligned, so read them back the same way.
+    env.write(|_, access| -> Result<(), Error> {
+        assert_eq!(access.fetch_add(&counts, "hits", 0)?, 7);
+        assert_eq!(access.fetch_add(&counts, "misses", 0)?, 1);
+        Ok(())
+    })
+    .unwrap();
+
+    env.write(|_, access| -> Result<(), Error> {
+        access.fetch_add(&counts, "max", u64::MAX)?;
+        match access.fetch_add(&counts, "max", 1) {
+            Err(Error::Overflow(context)) => assert_eq!(context.op, Some("fetch_add")),
+            other => panic!("expected overflow, got {:?}", other),
+        }
+        assert_eq!(access.fetch_add(&counts, "max", 0)?, u64::MAX);
+        Ok(())
+    })
+    .unwrap();
+
+    let txn = WriteTransaction::new(&*env).unwrap();
+    {
+        let mut access = txn.access();
+        let mut c = txn.cursor(&names).unwrap();
+
+        // Same size is overwritten in place, other sizes and new keys are put.
+        let new = access.update_at(&mut c, "a", |_| Some("one".to_owned()));
+        assert_eq!(new.unwrap().as_deref(), Some("one"));
+        let new = access.update_at(&mut c, "a", |old| Some(old.unwrap().to_uppercase()));
+        assert_eq!(new.unwrap().as_deref(), Some("ONE"));
+        assert_eq!(access.get(&names, "a").unwrap(), "ONE");
+        access
+            .update_at(&mut c, "a", |old| Some(format!("{}!", old.unwrap())))
+            .unwrap();
+        assert_eq!(access.get(&names, "a").unwrap(), "ONE!");
+        assert!(access.update_at(&mut c, "a", |_| None).unwrap().is_none());
+        assert!(access.get_opt(&names, "a").unwrap().is_none());
+
+        let mut c = txn.cursor(&counts).unwrap();
+        assert_eq!(access.fetch_add_at(&mut c, "hits", 3).unwrap(), 7);
+        assert_eq!(access.fetch_add_at(&mut c, "new", 3).unwrap(), 0);
+        assert_eq!(access.fetch_add_at(&mut c, "hits", 0).unwrap(), 10);
+        assert_eq!(access.fetch_add_at(&mut c, "new", 0).unwrap(), 3);
+        assert!(matches!(
+            access.fetch_add_at(&mut c, "max", 1),
+            Err(Error::Overflow(_))
+        ));
+    }
+    txn.commit().unwrap();
+}
+
+#[test]
+fn test_compare_and_swap() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = env
+        .open_db::<str, str, LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+    let dup_opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE | lmdb_zero::db::DUPSORT);
+    let dup_db = env
+        .open_db::<str, str, LmdbLayoutDupsort>(Some("tree2"), &dup_opts)
+        .unwrap();
+
+    let txn = WriteTransaction::new(&*env).unwrap();
+    {
+        let mut access = txn.access();
+
+        assert_eq!(
+            access
+                .compare_and_swap(&db, "a", None, Some("one"))
+                .unwrap(),
+            Ok(())
+        );
+        assert_eq!(
+            access
+                .compare_and_swap(&db, "a", None, Some("two"))
+                .unwrap(),
+            Err(Some("one".to_owned()))
+        );
+        assert_eq!(
+            access
+                .compare_and_swap(&db, "a", Some("two"), Some("three"))
+                .unwrap(),
+            Err(Some("one".to_owned()))
+        );
+        assert_eq!(
+            access
+                .compare_and_swap(&db, "a", Some("one"), Some("two"))
+                .unwrap(),
+            Ok(())
+        );
+        assert_eq!(access.get(&db, "a").unwrap(), "two");
+
+        assert_eq!(
+            access
+                .compare_and_swap(&db, "a", Some("two"), None)
+                .unwrap(),
+            Ok(())
+        );
+        assert_eq!(
+            access
+                .compare_and_swap(&db, "a", Some("two"), None)
+                .unwrap(),
+            Err(None)
+        );
+        assert!(access.get_opt(&db, "a").unwrap().is_none());
+
+        let put_flags = lmdb_zero::put::Flags::empty();
+        access.put(&dup_db, "k", "x", put_flags).unwrap();
+        access.put(&dup_db, "k", "y", put_flags).unwrap();
+
+        let mut c = txn.cursor(&dup_db).unwrap();
+        assert!(!access
+            .compare_and_swap_dup(&mut c, "k", "z", Some("w"))
+            .unwrap());
+        assert!(access
+            .compare_and_swap_dup(&mut c, "k", "x", Some("z"))
+            .unwrap());
+        assert!(access.compare_and_swap_dup(&mut c, "k", "y", None).unwrap());
+
+        let dups = c
+            .dups(&access, "k")
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(dups, ["z"]);
+    }
+    txn.commit().unwrap();
+}
+
+#[test]
+fn test_get_many() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = env
+        .open_db::<Ordered<u32>, str, LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+
+    env.write(|_, access| -> Result<(), Error> {
+        let put_flags = lmdb_zero::put::Flags::empty();
+        for i in (0..100).step_by(2) {
+            access.put(&db, &i, &i.to_string(), put_flags)?;
+        }
+        Ok(())
+    })
+    .unwrap();
+
+    let (found, empty) = env
+        .read(|txn, access| -> Result<_, Error> {
+            let found = access
+                .get_many(txn, &db, [50, 3, 98, 0, 51, 52, 50, 200])?
+                .into_iter()
+                .map(|val| val.map(str::to_owned))
+                .collect::<Vec<_>>();
+            let empty = access.get_many(txn, &db, Vec::<u32>::new())?;
+            Ok((found, empty.is_empty()))
+        })
+        .unwrap();
+    let expected = [
+        Some("50"),
+        None,
+        Some("98"),
+        Some("0"),
+        None,
+        Some("52"),
+        Some("50"),
+        None,
+    ];
+    assert_eq!(found, expected.map(|val| val.map(str::to_owned)));
+    assert!(empty);
+
+    // A transaction other than the accessor's is an error, not a panic.
+    env.write(|_, access| -> Result<(), Error> {
+        let other = ReadTransaction::new(&*env)?;
+        match access.get_many(&other, &db, [50]) {
+            Err(Error::Lmdb {
+                error: lmdb_zero::Error::Mismatch,
+                ..
+            }) => (),
+            other => panic!("expected a mismatch, got {:?}", other),
+        }
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,87 @@
+use lmdb_zero_typed::*;
+
+#[test]
+fn test_bulk_load() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(10_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = env
+        .open_db::<Ordered<u32>, str, LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+
+    let loader = BulkLoader::new(&db).chunk_size(7);
+    let count = loader
+        .load((0..100u32).map(|i| (i, i.to_string())))
+        .unwrap();
+    assert_eq!(count, 100);
+
+    // Loading more has to continue after the existing keys.
+    match loader.load([(50u32, "again")]) {
+        Err(Error::OutOfOrder { db, position, key }) => {
+            assert_eq!(db.as_deref(), Some("tree1"));
+            assert_eq!(position, 0);
+            assert_eq!(key, 50u32.to_be_bytes());
+        }
+        other => panic!("expected an out of order error, got {:?}", other),
+    }
+    assert_eq!(loader.load([(100u32, "100")]).unwrap(), 1);
+
+    // Chunks before the bad record stay committed.
+    let loader = BulkLoader::new(&db).chunk_size(2);
+    match loader.load([(200u32, "200"), (300, "300"), (300, "301")]) {
+        Err(Error::OutOfOrder { position, .. }) => assert_eq!(position, 2),
+        other => panic!("expected an out of order error, got {:?}", other),
+    }
+
+    env.read(|txn, access| -> Result<(), Error> {
+        assert_eq!(txn.db_stat(&db)?.entries, 103);
+        assert_eq!(access.get(&db, &42)?, "42");
+        assert_eq!(access.get(&db, &300)?, "300");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_bulk_load_dupsort() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE | lmdb_zero::db::DUPSORT);
+    let db = env
+        .open_db::<str, str, LmdbLayoutDupsort>(Some("tree1"), &opts)
+        .unwrap();
+
+    let loader = BulkLoader::new(&db);
+    let items = [("a", "1"), ("a", "2"), ("b", "1"), ("c", "0"), ("c", "9")];
+    assert_eq!(loader.load(items).unwrap(), 5);
+
+    match loader.load([("d", "2"), ("d", "1")]) {
+        Err(Error::OutOfOrder { position, key, .. }) => {
+            assert_eq!(position, 1);
+            assert_eq!(key, b"d");
+        }
+        other => panic!("expected an out of order error, got {:?}", other),
+    }
+
+    let dups = env
+        .read(|txn, access| -> Result<_, Error> {
+            let mut c = txn.cursor(&db)?;
+            c.dups(access, "c")?
+                .map(|val| val.map(str::to_owned))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap();
+    assert_eq!(dups, ["0", "9"]);
+}
@@ -0,0 +1,197 @@
+use std::borrow::Cow;
+
+use lmdb_zero_typed::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+struct PointCodec;
+
+impl Codec for PointCodec {
+    type Item = Point;
+    type Decoded<'a> = Point;
+
+    fn encode(item: &Point) -> lmdb_zero::Result<Cow<'_, [u8]>> {
+        let mut bytes = Vec::with_capacity(8);
+        bytes.extend_from_slice(&item.x.to_le_bytes());
+        bytes.extend_from_slice(&item.y.to_le_bytes());
+        Ok(Cow::Owned(bytes))
+    }
+
+    fn decode(bytes: &[u8]) -> lmdb_zero::Result<Point> {
+        if bytes.len() != 8 {
+            return Err(lmdb_zero::Error::ValRejected(format!(
+                "expected 8 bytes, got {}",
+                bytes.len()
+            )));
+        }
+        Ok(Point {
+            x: i32::from_le_bytes(bytes[..4].try_into().unwrap()),
+            y: i32::from_le_bytes(bytes[4..].try_into().unwrap()),
+        })
+    }
+}
+
+/// Stores ASCII strings only.
+struct AsciiCodec;
+
+impl Codec for AsciiCodec {
+    type Item = str;
+    type Decoded<'a> = &'a str;
+
+    fn encode(item: &str) -> lmdb_zero::Result<Cow<'_, [u8]>> {
+        if !item.is_ascii() {
+            return Err(lmdb_zero::Error::ValRejected(format!(
+                "{:?} is not ASCII",
+                item
+            )));
+        }
+        Ok(Cow::Borrowed(item.as_bytes()))
+    }
+
+    fn decode(bytes: &[u8]) -> lmdb_zero::Result<&str> {
+        <str as Codec>::decode(bytes)
+    }
+}
+
+#[test]
+fn test_codec() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = unsafe {
+        let mut eb = lmdb_zero::EnvBuilder::new().unwrap();
+        eb.set_mapsize(1_000_000).unwrap();
+        eb.set_maxdbs(5).unwrap();
+
+        eb.set_maxreaders(64).unwrap();
+
+        eb.open(&tmp.path().to_string_lossy(), lmdb_zero::open::NOTLS, 0o600)
+            .unwrap()
+    };
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db =
+        Database::<str, PointCodec, LmdbLayoutDefault>::open(&env, Some("tree1"), &opts).unwrap();
+
+    let put_flags = lmdb_zero::put::Flags::empty();
+
+    let txn = WriteTransaction::new(&env).unwrap();
+    let mut access = txn.access();
+
+    access
+        .put(&db, "origin", &Point { x: 0, y: 0 }, put_flags)
+        .unwrap();
+    access
+        .put(&db, "unit", &Point { x: 1, y: -1 }, put_flags)
+        .unwrap();
+
+    assert_eq!(access.get(&db, "origin").unwrap(), Point { x: 0, y: 0 });
+    assert_eq!(access.get(&db, "unit").unwrap(), Point { x: 1, y: -1 });
+    assert!(access.get(&db, "missing").to_opt().unwrap().is_none());
+
+    let mut c = txn.cursor(&db).unwrap();
+    assert_eq!(c.first(&access).unwrap(), ("origin", Point { x: 0, y: 0 }));
+    assert_eq!(c.next(&access).unwrap(), ("unit", Point { x: 1, y: -1 }));
+    assert_eq!(c.seek_k(&access, "origin").unwrap(), Point { x: 0, y: 0 });
+
+    c.put(&mut access, "far", &Point { x: 100, y: 200 }, put_flags)
+        .unwrap();
+    assert_eq!(
+        c.seek_range_k(&access, "e").unwrap(),
+        ("far", Point { x: 100, y: 200 })
+    );
+
+    access.del_key(&db, "far").unwrap();
+    assert!(access.get(&db, "far").to_opt().unwrap().is_none());
+}
+
+#[test]
+fn test_codec_decode_error() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = unsafe {
+        let mut eb = lmdb_zero::EnvBuilder::new().unwrap();
+        eb.set_mapsize(1_000_000).unwrap();
+        eb.set_maxdbs(5).unwrap();
+
+        eb.set_maxreaders(64).unwrap();
+
+        eb.open(&tmp.path().to_string_lossy(), lmdb_zero::open::NOTLS, 0o600)
+            .unwrap()
+    };
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let bytes = Database::<str, [u8], LmdbLayoutDefault>::open(&env, Some("tree1"), &opts).unwrap();
+
+    {
+        let txn = WriteTransaction::new(&env).unwrap();
+        {
+            let mut access = txn.access();
+            access
+                .put(&bytes, "short", b"abc", lmdb_zero::put::Flags::empty())
+                .unwrap();
+        }
+        txn.commit().unwrap();
+    }
+    drop(bytes);
+
+    let points = Database::<str, PointCodec, LmdbLayoutDefault>::open(
+        &env,
+        Some("tree1"),
+        &lmdb_zero::DatabaseOptions::defaults(),
+    )
+    .unwrap();
+
+    let txn = ReadTransaction::new(&env).unwrap();
+    let access = txn.access();
+    match access.get(&points, "short") {
+        Err(Error::Decode { context, .. }) => {
+            assert_eq!(context.op, Some("get"));
+            assert_eq!(context.db.as_deref(), Some("tree1"));
+        }
+        other => panic!("expected a decode error, got {:?}", other.map(|_| ())),
+    }
+    assert!(access.get(&points, "long").unwrap_err().is_not_found());
+}
+
+#[test]
+fn test_codec_encode_error() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = env
+        .open_db::<AsciiCodec, str, LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+
+    let result =
+        env.write(|_, access| access.put(&db, "caf\u{e9}", "", lmdb_zero::put::Flags::empty()));
+    match result {
+        Err(Error::Encode { context, .. }) => {
+            assert_eq!(context.op, Some("put"));
+            assert_eq!(context.db.as_deref(), Some("tree1"));
+        }
+        other => panic!("expected an encode error, got {:?}", other),
+    }
+
+    // Errors that did not come from LMDB do not convert back.
+    let err = env
+        .read(|_, access| access.get(&db, "caf\u{e9}").map(|_| ()))
+        .unwrap_err();
+    assert!(lmdb_zero::Error::try_from(err).is_err());
+    let err = env
+        .read(|_, access| access.get(&db, "cafe").map(|_| ()))
+        .unwrap_err();
+    assert!(matches!(
+        lmdb_zero::Error::try_from(err),
+        Ok(lmdb_zero::Error::Code(lmdb_zero::error::NOTFOUND))
+    ));
+}
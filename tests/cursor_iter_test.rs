@@ -60,3 +60,454 @@ fn test_cursor_iter() {
     assert_eq!(iter.next().unwrap().unwrap(), r!(3, 6));
     assert!(iter.next().is_none());
 }
+
+fn range_keys(iter: RangeIter<Raw, Raw, LmdbLayoutDefault>) -> Vec<u32> {
+    iter.map(|kv| {
+        let (&Raw(k), &Raw(v)) = kv.unwrap();
+        assert_eq!(v, k * 2);
+        k
+    })
+    .collect()
+}
+
+#[test]
+fn test_range_iter() {
+    use std::ops::Bound;
+
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = unsafe {
+        let mut eb = lmdb_zero::EnvBuilder::new().unwrap();
+        eb.set_mapsize(1_000_000).unwrap();
+        eb.set_maxdbs(5).unwrap();
+
+        eb.set_maxreaders(64).unwrap();
+
+        eb.open(&tmp.path().to_string_lossy(), lmdb_zero::open::NOTLS, 0o600)
+            .unwrap()
+    };
+
+    // INTEGERKEY sorts little-endian keys numerically, which bytewise
+    // comparison would get wrong for 256 vs 1.
+    let opts1 = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::INTEGERKEY | lmdb_zero::db::CREATE);
+    let db = Database::<Raw, Raw, LmdbLayoutDefault>::open(&env, Some("tree1"), &opts1).unwrap();
+    let opts2 = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let dup_db = Database::<Ordered<u32>, Ordered<u32>, LmdbLayoutDupsort>::open(
+        &env,
+        Some("tree2"),
+        &opts2,
+    )
+    .unwrap();
+
+    // REVERSEKEY compares keys from their last byte.
+    let opts3 = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::REVERSEKEY | lmdb_zero::db::CREATE);
+    let rev_db =
+        Database::<str, str, LmdbLayoutDefault>::open(&env, Some("tree3"), &opts3).unwrap();
+
+    let put_flags = lmdb_zero::put::Flags::empty();
+
+    let txn = WriteTransaction::new(&env).unwrap();
+    let mut access = txn.access();
+    for i in [1, 2, 3, 256, 512] {
+        access.put(&db, r!(i), r!(i * 2), put_flags).unwrap();
+        for j in 0..3 {
+            access.put(&dup_db, &i, &j, put_flags).unwrap();
+        }
+    }
+
+    let mut cursor = txn.cursor(&db).unwrap();
+
+    assert_eq!(
+        range_keys(cursor.range(&access, ..).unwrap()),
+        [1, 2, 3, 256, 512]
+    );
+    assert_eq!(
+        range_keys(cursor.range(&access, Raw(2)..Raw(256)).unwrap()),
+        [2, 3]
+    );
+    assert_eq!(
+        range_keys(cursor.range(&access, Raw(2)..=Raw(256)).unwrap()),
+        [2, 3, 256]
+    );
+    assert_eq!(
+        range_keys(cursor.range(&access, Raw(4)..).unwrap()),
+        [256, 512]
+    );
+    assert_eq!(
+        range_keys(
+            cursor
+                .range(&access, (Bound::Excluded(Raw(3)), Bound::Unbounded))
+                .unwrap()
+        ),
+        [256, 512]
+    );
+    assert!(range_keys(cursor.range(&access, ..Raw(1)).unwrap()).is_empty());
+    assert!(range_keys(cursor.range(&access, Raw(513)..).unwrap()).is_empty());
+
+    let mut dup_cursor = txn.cursor(&dup_db).unwrap();
+    let items: Vec<(u32, u32)> = dup_cursor
+        .range(&access, (Bound::Excluded(2), Bound::Included(256)))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(
+        items,
+        [(3, 0), (3, 1), (3, 2), (256, 0), (256, 1), (256, 2)]
+    );
+
+    for key in ["ab", "ba", "ca"] {
+        access.put(&rev_db, key, "", put_flags).unwrap();
+    }
+    let mut rev_cursor = txn.cursor(&rev_db).unwrap();
+    let keys: Vec<String> = rev_cursor
+        .range(&access, (Bound::Included("ca"), Bound::Unbounded))
+        .unwrap()
+        .map(|item| item.unwrap().0.to_owned())
+        .collect();
+    assert_eq!(keys, ["ca", "ab"]);
+}
+
+#[test]
+fn test_double_ended_iter() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = unsafe {
+        let mut eb = lmdb_zero::EnvBuilder::new().unwrap();
+        eb.set_mapsize(1_000_000).unwrap();
+        eb.set_maxdbs(5).unwrap();
+
+        eb.set_maxreaders(64).unwrap();
+
+        eb.open(&tmp.path().to_string_lossy(), lmdb_zero::open::NOTLS, 0o600)
+            .unwrap()
+    };
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db =
+        Database::<Ordered<u64>, str, LmdbLayoutDefault>::open(&env, Some("tree1"), &opts).unwrap();
+    let dup_db =
+        Database::<Ordered<u32>, Ordered<u32>, LmdbLayoutDupsort>::open(&env, Some("tree2"), &opts)
+            .unwrap();
+
+    let put_flags = lmdb_zero::put::Flags::empty();
+
+    {
+        let txn = WriteTransaction::new(&env).unwrap();
+        {
+            let mut access = txn.access();
+            for t in 1..=10u64 {
+                access
+                    .put(&db, &(t * 100), &format!("event{}", t), put_flags)
+                    .unwrap();
+            }
+            for i in 1..=3 {
+                for j in 0..3 {
+                    access.put(&dup_db, &i, &j, put_flags).unwrap();
+                }
+            }
+        }
+        txn.commit().unwrap();
+    }
+
+    let txn = ReadTransaction::new(&env).unwrap();
+    let access = txn.access();
+
+    let latest: Vec<u64> = txn
+        .iter_rev(&db, &access)
+        .unwrap()
+        .take(3)
+        .map(|kv| kv.unwrap().0)
+        .collect();
+    assert_eq!(latest, [1000, 900, 800]);
+
+    let keys = |iter: &mut dyn Iterator<Item = Result<(u64, &str), Error>>| -> Vec<u64> {
+        iter.map(|kv| kv.unwrap().0).collect()
+    };
+    assert_eq!(
+        keys(&mut txn.range_rev(&db, &access, 250..=700).unwrap()),
+        [700, 600, 500, 400, 300]
+    );
+    assert_eq!(
+        keys(&mut txn.range_rev(&db, &access, 250..700).unwrap()),
+        [600, 500, 400, 300]
+    );
+    assert!(keys(&mut txn.range_rev(&db, &access, ..=50).unwrap()).is_empty());
+    assert!(keys(&mut txn.range_rev(&db, &access, 1001..).unwrap()).is_empty());
+    assert_eq!(
+        keys(&mut txn.range(&db, &access, 500..=500).unwrap()),
+        [500]
+    );
+
+    // Alternating ends meet in the middle without repeating an entry.
+    let mut iter = txn.range(&db, &access, 200..=600).unwrap();
+    assert_eq!(iter.next().unwrap().unwrap().0, 200);
+    assert_eq!(iter.next_back().unwrap().unwrap().0, 600);
+    assert_eq!(iter.next_back().unwrap().unwrap().0, 500);
+    assert_eq!(iter.next().unwrap().unwrap().0, 300);
+    assert_eq!(iter.next().unwrap().unwrap().0, 400);
+    assert!(iter.next_back().is_none());
+    assert!(iter.next().is_none());
+
+    let mut iter = txn.iter(&db, &access).unwrap();
+    for t in 1..=5 {
+        assert_eq!(iter.next_back().unwrap().unwrap().0, (11 - t) * 100);
+        assert_eq!(iter.next().unwrap().unwrap().0, t * 100);
+    }
+    assert!(iter.next().is_none());
+
+    let items: Vec<(u32, u32)> = txn
+        .range_rev(&dup_db, &access, 2..)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(items, [(3, 2), (3, 1), (3, 0), (2, 2), (2, 1), (2, 0)]);
+    let items: Vec<(u32, u32)> = txn
+        .range_rev(&dup_db, &access, ..=2)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(items, [(2, 2), (2, 1), (2, 0), (1, 2), (1, 1), (1, 0)]);
+
+    // The ends also meet among the duplicates of a key, and on the dirty
+    // pages of a write transaction.
+    let wtxn = WriteTransaction::new(&env).unwrap();
+    wtxn.access().put(&dup_db, &2, &3, put_flags).unwrap();
+    let waccess = wtxn.access();
+    let mut iter = wtxn.range(&dup_db, &waccess, 2..=2).unwrap();
+    assert_eq!(iter.next().unwrap().unwrap(), (2, 0));
+    assert_eq!(iter.next_back().unwrap().unwrap(), (2, 3));
+    assert_eq!(iter.next().unwrap().unwrap(), (2, 1));
+    assert_eq!(iter.next_back().unwrap().unwrap(), (2, 2));
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+}
+
+#[test]
+fn test_prefix_iter() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = unsafe {
+        let mut eb = lmdb_zero::EnvBuilder::new().unwrap();
+        eb.set_mapsize(1_000_000).unwrap();
+        eb.set_maxdbs(5).unwrap();
+
+        eb.set_maxreaders(64).unwrap();
+
+        eb.open(&tmp.path().to_string_lossy(), lmdb_zero::open::NOTLS, 0o600)
+            .unwrap()
+    };
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let paths = Database::<str, str, LmdbLayoutDefault>::open(&env, Some("tree1"), &opts).unwrap();
+    let bytes = Database::<[u8], str, LmdbLayoutDefault>::open(&env, Some("tree2"), &opts).unwrap();
+    let tuples = Database::<Ordered<(String, u32)>, str, LmdbLayoutDefault>::open(
+        &env,
+        Some("tree3"),
+        &opts,
+    )
+    .unwrap();
+    let names =
+        Database::<Ordered<String>, str, LmdbLayoutDefault>::open(&env, Some("tree4"), &opts)
+            .unwrap();
+
+    let put_flags = lmdb_zero::put::Flags::empty();
+
+    let txn = WriteTransaction::new(&env).unwrap();
+    let mut access = txn.access();
+    for path in ["acme/alice", "acme/bob", "acme", "acme2/carol", "beta/dave"] {
+        access.put(&paths, path, path, put_flags).unwrap();
+    }
+    for key in [
+        &[0xfe, 0xff][..],
+        &[0xff],
+        &[0xff, 0xff],
+        &[0xff, 0xff, 0x00],
+        &[0xff, 0xff, 0xff],
+    ] {
+        access.put(&bytes, key, "", put_flags).unwrap();
+    }
+    for (user, n) in [("al", 1), ("al", 2), ("al\0", 0), ("alice", 3), ("bo", 4)] {
+        access
+            .put(&tuples, &(user.to_owned(), n), "", put_flags)
+            .unwrap();
+        access.put(&names, &user.to_owned(), "", put_flags).unwrap();
+    }
+
+    let mut c = txn.cursor(&paths).unwrap();
+    let found: Vec<&str> = c
+        .prefix(&access, "acme/")
+        .unwrap()
+        .map(|kv| kv.unwrap().0)
+        .collect();
+    assert_eq!(found, ["acme/alice", "acme/bob"]);
+    let found: Vec<&str> = c
+        .prefix(&access, "acme")
+        .unwrap()
+        .map(|kv| kv.unwrap().0)
+        .collect();
+    assert_eq!(found, ["acme", "acme/alice", "acme/bob", "acme2/carol"]);
+    assert_eq!(c.prefix(&access, "zeta").unwrap().count(), 0);
+    assert_eq!(c.prefix(&access, "").unwrap().count(), 5);
+
+    let mut c = txn.cursor(&bytes).unwrap();
+    let found: Vec<&[u8]> = c
+        .prefix(&access, &[0xff, 0xff][..])
+        .unwrap()
+        .map(|kv| kv.unwrap().0)
+        .collect();
+    assert_eq!(
+        found,
+        [&[0xff, 0xff][..], &[0xff, 0xff, 0x00], &[0xff, 0xff, 0xff]]
+    );
+    assert_eq!(
+        c.prefix(&access, &[0xff, 0xff, 0xff, 0xff][..])
+            .unwrap()
+            .count(),
+        0
+    );
+
+    let mut c = txn.cursor(&tuples).unwrap();
+    let found: Vec<(String, u32)> = c
+        .prefix(&access, &("al".to_owned(),))
+        .unwrap()
+        .map(|kv| kv.unwrap().0)
+        .collect();
+    assert_eq!(found, [("al".to_owned(), 1), ("al".to_owned(), 2)]);
+
+    let mut c = txn.cursor(&names).unwrap();
+    let found: Vec<String> = c
+        .prefix(&access, "al")
+        .unwrap()
+        .map(|kv| kv.unwrap().0)
+        .collect();
+    assert_eq!(found, ["al", "al\0", "alice"]);
+}
+
+#[test]
+fn test_dups_and_groups() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = unsafe {
+        let mut eb = lmdb_zero::EnvBuilder::new().unwrap();
+        eb.set_mapsize(1_000_000).unwrap();
+        eb.set_maxdbs(5).unwrap();
+
+        eb.set_maxreaders(64).unwrap();
+
+        eb.open(&tmp.path().to_string_lossy(), lmdb_zero::open::NOTLS, 0o600)
+            .unwrap()
+    };
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = Database::<str, str, LmdbLayoutDupsort>::open(&env, Some("tree1"), &opts).unwrap();
+
+    let put_flags = lmdb_zero::put::Flags::empty();
+
+    let txn = WriteTransaction::new(&env).unwrap();
+    let mut access = txn.access();
+    for (k, v) in [
+        ("fruit", "apple"),
+        ("fruit", "pear"),
+        ("fruit", "fig"),
+        ("nut", "pecan"),
+        ("veg", "leek"),
+        ("veg", "kale"),
+    ] {
+        access.put(&db, k, v, put_flags).unwrap();
+    }
+
+    let mut c = txn.cursor(&db).unwrap();
+    let fruit: Vec<&str> = c
+        .dups(&access, "fruit")
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(fruit, ["apple", "fig", "pear"]);
+    assert_eq!(c.dups(&access, "nut").unwrap().count(), 1);
+    assert_eq!(c.dups(&access, "grain").unwrap().count(), 0);
+
+    let groups: Vec<(&str, Vec<&str>)> = c
+        .groups(&access)
+        .map(|group| {
+            let (key, dups) = group.unwrap();
+            (key, dups.map(Result::unwrap).collect())
+        })
+        .collect();
+    assert_eq!(
+        groups,
+        [
+            ("fruit", vec!["apple", "fig", "pear"]),
+            ("nut", vec!["pecan"]),
+            ("veg", vec!["kale", "leek"]),
+        ]
+    );
+
+    // Groups can be advanced before their values are read, and partially
+    // read groups do not disturb the key iteration.
+    let mut groups = c.groups(&access);
+    let (_, mut fruit) = groups.next().unwrap().unwrap();
+    let (_, nut) = groups.next().unwrap().unwrap();
+    assert_eq!(fruit.next().unwrap().unwrap(), "apple");
+    assert_eq!(fruit.next().unwrap().unwrap(), "fig");
+    let (veg_key, veg) = groups.next().unwrap().unwrap();
+    assert!(groups.next().is_none());
+    assert_eq!(fruit.map(Result::unwrap).collect::<Vec<_>>(), ["pear"]);
+    assert_eq!(veg_key, "veg");
+    assert_eq!(
+        veg.map(Result::unwrap).collect::<Vec<_>>(),
+        ["kale", "leek"]
+    );
+    assert_eq!(nut.map(Result::unwrap).collect::<Vec<_>>(), ["pecan"]);
+}
+
+#[test]
+fn test_page_iter() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = unsafe {
+        let mut eb = lmdb_zero::EnvBuilder::new().unwrap();
+        eb.set_mapsize(10_000_000).unwrap();
+        eb.set_maxdbs(5).unwrap();
+
+        eb.set_maxreaders(64).unwrap();
+
+        eb.open(&tmp.path().to_string_lossy(), lmdb_zero::open::NOTLS, 0o600)
+            .unwrap()
+    };
+
+    let opts = lmdb_zero::DatabaseOptions::new(
+        lmdb_zero::db::INTEGERDUP | lmdb_zero::db::INTEGERKEY | lmdb_zero::db::CREATE,
+    );
+    let db = Database::<Raw, Raw, LmdbLayoutDupfixed>::open(&env, Some("tree1"), &opts).unwrap();
+
+    let put_flags = lmdb_zero::put::Flags::empty();
+
+    let txn = WriteTransaction::new(&env).unwrap();
+    let mut access = txn.access();
+    let postings: Vec<Raw> = (0..20000).map(Raw).collect();
+    let mut c = txn.cursor(&db).unwrap();
+    c.put_multiple(&mut access, r!(1), &postings, put_flags)
+        .unwrap();
+    for i in 0..3 {
+        access.put(&db, r!(0), r!(i), put_flags).unwrap();
+        access.put(&db, r!(2), r!(i), put_flags).unwrap();
+    }
+
+    let pages: Vec<&[Raw]> = c
+        .pages(&access, r!(1))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert!(pages.len() > 1);
+    assert_eq!(pages.concat(), postings);
+
+    let mut expected = 0;
+    for val in c.pages(&access, r!(1)).unwrap().values() {
+        assert_eq!({ val.unwrap().0 }, expected);
+        expected += 1;
+    }
+    assert_eq!(expected, 20000);
+
+    assert_eq!(c.pages(&access, r!(2)).unwrap().values().count(), 3);
+    assert_eq!(c.pages(&access, r!(3)).unwrap().count(), 0);
+}
@@ -439,3 +439,83 @@ fn test_repr_from_as_raw() {
     c3.del(&mut access, del_flags).unwrap();
     assert!(c3.last(&access).to_opt().unwrap().is_none());
 }
+
+#[test]
+fn test_opt() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = unsafe {
+        let mut eb = lmdb_zero::EnvBuilder::new().unwrap();
+        eb.set_mapsize(1_000_000).unwrap();
+        eb.set_maxdbs(5).unwrap();
+
+        eb.set_maxreaders(64).unwrap();
+
+        eb.open(&tmp.path().to_string_lossy(), lmdb_zero::open::NOTLS, 0o600)
+            .unwrap()
+    };
+
+    let opts = lmdb_zero::DatabaseOptions::new(
+        lmdb_zero::db::INTEGERDUP
+            | lmdb_zero::db::INTEGERKEY
+            | lmdb_zero::db::CREATE
+            | lmdb_zero::db::DUPFIXED
+            | lmdb_zero::db::DUPSORT,
+    );
+    let db = Database::<Raw, Raw, LmdbLayoutDupfixed>::open(&env, Some("tree1"), &opts).unwrap();
+
+    let txn = WriteTransaction::new(&env).unwrap();
+    let mut access = txn.access();
+    let put_flags = lmdb_zero::put::Flags::empty();
+
+    let mut c = txn.cursor(&db).unwrap();
+    assert!(c.first_opt(&access).unwrap().is_none());
+    assert!(c.last_opt(&access).unwrap().is_none());
+    assert!(access.get_opt(&db, r!(1)).unwrap().is_none());
+
+    c.put(&mut access, r!(1), r!(10), put_flags).unwrap();
+    c.put(&mut access, r!(1), r!(11), put_flags).unwrap();
+    c.put(&mut access, r!(3), r!(30), put_flags).unwrap();
+
+    // Found records come back as `Some`, missing ones as `None`.
+    assert_eq!(access.get_opt(&db, r!(1)).unwrap(), Some(r!(10)));
+    assert!(access.get_opt(&db, r!(2)).unwrap().is_none());
+
+    assert_eq!(c.first_opt(&access).unwrap(), Some(r!(1, 10)));
+    assert_eq!(c.get_current_opt(&access).unwrap(), Some(r!(1, 10)));
+    assert!(c.prev_opt(&access).unwrap().is_none());
+    assert_eq!(c.next_dup_opt(&access).unwrap(), Some(r!(1, 11)));
+    assert!(c.next_dup_opt(&access).unwrap().is_none());
+    assert_eq!(c.prev_dup_opt(&access).unwrap(), Some(r!(1, 10)));
+    assert!(c.prev_dup_opt(&access).unwrap().is_none());
+    assert_eq!(c.last_dup_opt(&access).unwrap(), Some(r!(11)));
+    assert_eq!(c.first_dup_opt(&access).unwrap(), Some(r!(10)));
+    assert_eq!(
+        c.get_multiple_opt(&access).unwrap(),
+        Some(&[Raw(10), Raw(11)][..])
+    );
+    assert!(c.next_multiple_opt(&access).unwrap().is_none());
+    assert_eq!(c.next_nodup_opt(&access).unwrap(), Some(r!(3, 30)));
+    assert!(c.next_nodup_opt(&access).unwrap().is_none());
+    assert!(c.next_opt(&access).unwrap().is_none());
+    assert_eq!(c.prev_nodup_opt(&access).unwrap(), Some(r!(1, 11)));
+    assert!(c.prev_nodup_opt(&access).unwrap().is_none());
+    assert_eq!(c.last_opt(&access).unwrap(), Some(r!(3, 30)));
+
+    assert_eq!(c.seek_k_opt(&access, r!(3)).unwrap(), Some(r!(30)));
+    assert!(c.seek_k_opt(&access, r!(2)).unwrap().is_none());
+    assert_eq!(c.seek_k_both_opt(&access, r!(1)).unwrap(), Some(r!(1, 10)));
+    assert!(c.seek_k_both_opt(&access, r!(2)).unwrap().is_none());
+    assert_eq!(c.seek_range_k_opt(&access, r!(2)).unwrap(), Some(r!(3, 30)));
+    assert!(c.seek_range_k_opt(&access, r!(4)).unwrap().is_none());
+    assert_eq!(c.seek_kv_opt(r!(1), r!(11)).unwrap(), Some(()));
+    assert!(c.seek_kv_opt(r!(1), r!(12)).unwrap().is_none());
+    assert_eq!(
+        c.seek_k_nearest_v_opt(&access, r!(1), r!(5)).unwrap(),
+        Some(r!(10))
+    );
+    assert!(c
+        .seek_k_nearest_v_opt(&access, r!(1), r!(12))
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,106 @@
+use lmdb_zero::traits::*;
+
+use lmdb_zero_typed::*;
+
+#[repr(C, packed)]
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+struct Raw(u32);
+
+unsafe impl LmdbRaw for Raw {}
+
+#[test]
+fn test_layout_flags() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = unsafe {
+        let mut eb = lmdb_zero::EnvBuilder::new().unwrap();
+        eb.set_mapsize(1_000_000).unwrap();
+        eb.set_maxdbs(5).unwrap();
+
+        eb.set_maxreaders(64).unwrap();
+
+        eb.open(&tmp.path().to_string_lossy(), lmdb_zero::open::NOTLS, 0o600)
+            .unwrap()
+    };
+
+    let create = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let existing = lmdb_zero::DatabaseOptions::defaults();
+
+    // CREATE picks up the DUPSORT flag from the layout.
+    {
+        let db =
+            Database::<Raw, Raw, LmdbLayoutDupsort>::open(&env, Some("tree1"), &create).unwrap();
+        let txn = WriteTransaction::new(&env).unwrap();
+        let flags = txn.db_flags(&db).unwrap();
+        assert!(flags.contains(lmdb_zero::db::DUPSORT));
+        assert!(!flags.contains(lmdb_zero::db::DUPFIXED));
+    }
+
+    assert!(matches!(
+        Database::<Raw, Raw, LmdbLayoutDefault>::open(&env, Some("tree1"), &existing),
+        Err(Error::LayoutMismatch { .. })
+    ));
+    assert!(matches!(
+        Database::<Raw, Raw, LmdbLayoutDupfixed>::open(&env, Some("tree1"), &existing),
+        Err(Error::LayoutMismatch { .. })
+    ));
+    assert!(Database::<Raw, Raw, LmdbLayoutDupsort>::open(&env, Some("tree1"), &existing).is_ok());
+
+    {
+        Database::<Raw, Raw, LmdbLayoutDupfixed>::open(&env, Some("tree2"), &create).unwrap();
+    }
+
+    assert!(matches!(
+        Database::<Raw, Raw, LmdbLayoutDefault>::open(&env, Some("tree2"), &existing),
+        Err(Error::LayoutMismatch { .. })
+    ));
+    assert!(matches!(
+        Database::<Raw, Raw, LmdbLayoutDupsort>::open(&env, Some("tree2"), &existing),
+        Err(Error::LayoutMismatch { .. })
+    ));
+    assert!(Database::<Raw, Raw, LmdbLayoutDupfixed>::open(&env, Some("tree2"), &existing).is_ok());
+
+    // Key ordering flags must be requested consistently.
+    let integer_key =
+        lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE | lmdb_zero::db::INTEGERKEY);
+    {
+        Database::<Raw, Raw, LmdbLayoutDefault>::open(&env, Some("tree3"), &integer_key).unwrap();
+    }
+
+    assert!(matches!(
+        Database::<Raw, Raw, LmdbLayoutDefault>::open(&env, Some("tree3"), &existing),
+        Err(Error::LayoutMismatch { .. })
+    ));
+    assert!(Database::<Raw, Raw, LmdbLayoutDefault>::open(
+        &env,
+        Some("tree3"),
+        &lmdb_zero::DatabaseOptions::new(lmdb_zero::db::INTEGERKEY)
+    )
+    .is_ok());
+
+    // Explicit flags that disagree with the layout are rejected before the
+    // database is created.
+    let dupsort = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE | lmdb_zero::db::DUPSORT);
+    assert!(matches!(
+        Database::<Raw, Raw, LmdbLayoutDefault>::open(&env, Some("tree4"), &dupsort),
+        Err(Error::LayoutMismatch { .. })
+    ));
+    assert!(matches!(
+        Database::<Raw, Raw, LmdbLayoutDefault>::open(&env, Some("tree4"), &existing),
+        Err(Error::NotFound(_))
+    ));
+
+    // Custom comparators are rejected, since typed ranges cannot follow them.
+    let mut custom = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    custom.sort_keys_as::<lmdb_zero::Unaligned<u32>>();
+    assert!(matches!(
+        Database::<Raw, Raw, LmdbLayoutDefault>::open(&env, Some("tree5"), &custom),
+        Err(Error::CustomComparator { .. })
+    ));
+    let mut custom = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    custom.sort_values_as::<lmdb_zero::Unaligned<u32>>();
+    assert!(matches!(
+        Database::<Raw, Raw, LmdbLayoutDupsort>::open(&env, Some("tree5"), &custom),
+        Err(Error::CustomComparator { .. })
+    ));
+}
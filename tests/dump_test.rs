@@ -0,0 +1,135 @@
+use lmdb_zero_typed::*;
+
+#[test]
+fn test_dump_load() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE | lmdb_zero::db::DUPSORT);
+    let db = env
+        .open_db::<str, [u8], LmdbLayoutDupsort>(Some("tree1"), &opts)
+        .unwrap();
+    let copy = env
+        .open_db::<str, [u8], LmdbLayoutDupsort>(Some("tree2"), &opts)
+        .unwrap();
+
+    env.write(|_, access| -> Result<(), Error> {
+        let put_flags = lmdb_zero::put::Flags::empty();
+        access.put(&db, "a b", b"x\\y", put_flags)?;
+        access.put(&db, "a b", b"\x00\xff", put_flags)?;
+        access.put(&db, "c", b"", put_flags)
+    })
+    .unwrap();
+
+    let (bytes, print) = env
+        .read(|txn, access| -> Result<_, Error> {
+            let mut bytes = Vec::new();
+            let mut print = Vec::new();
+            dump::dump(txn, access, &db, &mut bytes)?;
+            dump::dump_with_format(txn, access, &db, DumpFormat::Print, &mut print)?;
+            Ok((
+                String::from_utf8(bytes).unwrap(),
+                String::from_utf8(print).unwrap(),
+            ))
+        })
+        .unwrap();
+
+    assert!(bytes.starts_with("VERSION=3\nformat=bytevalue\ndatabase=tree1\ntype=btree\n"));
+    assert!(bytes.contains("\nduplicates=1\ndupsort=1\n"));
+    assert!(bytes.ends_with("HEADER=END\n 612062\n 00ff\n 612062\n 785c79\n 63\n \nDATA=END\n"));
+    assert!(print.contains("format=print\n"));
+    assert!(print.ends_with("HEADER=END\n a b\n \\00\\ff\n a b\n x\\\\y\n c\n \nDATA=END\n"));
+
+    // Files written by mdb_dump from the same database, which differ from ours
+    // only in the environment settings.
+    let fixtures = [
+        include_str!("fixtures/dupsort-bytevalue.txt"),
+        include_str!("fixtures/dupsort-print.txt"),
+    ];
+    assert_eq!(without_settings(&bytes), without_settings(fixtures[0]));
+    assert_eq!(without_settings(&print), without_settings(fixtures[1]));
+
+    for text in [bytes.as_str(), print.as_str()].into_iter().chain(fixtures) {
+        let count = env
+            .write(|txn, access| -> Result<_, Error> {
+                access.clear_db(&copy)?;
+                dump::load(txn, access, &copy, text.as_bytes())
+            })
+            .unwrap();
+        assert_eq!(count, 3);
+
+        let entries = env
+            .read(|txn, access| -> Result<_, Error> {
+                txn.iter(&copy, access)?
+                    .owned()
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .unwrap();
+        assert_eq!(
+            entries,
+            [
+                ("a b".to_owned(), vec![0, 0xff]),
+                ("a b".to_owned(), b"x\\y".to_vec()),
+                ("c".to_owned(), vec![]),
+            ]
+        );
+    }
+}
+
+fn without_settings(text: &str) -> Vec<&str> {
+    text.lines()
+        .filter(|line| {
+            !["mapsize=", "maxreaders=", "db_pagesize="]
+                .iter()
+                .any(|setting| line.starts_with(setting))
+        })
+        .collect()
+}
+
+#[test]
+fn test_load_errors() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = env
+        .open_db::<str, str, LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+
+    let load = |text: &str| {
+        let txn = WriteTransaction::new(&*env).unwrap();
+        let mut access = txn.access();
+        dump::load(&txn, &mut access, &db, text.as_bytes())
+    };
+
+    // Headers written by mdb_dump, with settings that are ignored.
+    let header = "VERSION=3\nformat=bytevalue\ntype=btree\nmapsize=1048576\nmaxreaders=126\n\
+                  db_pagesize=4096\nHEADER=END\n";
+    assert_eq!(load(&format!("{} 61\n 62\nDATA=END\n", header)).unwrap(), 1);
+
+    let dupsort = header.replace("HEADER=END", "duplicates=1\ndupsort=1\nHEADER=END");
+    let cases = [
+        (format!("{}DATA=END\n", dupsort), 9),
+        (format!("{} 6\n 62\nDATA=END\n", header), 8),
+        (format!("{} 61\n 6x\nDATA=END\n", header), 9),
+        (format!("{}61\n 62\nDATA=END\n", header), 8),
+        (format!("{} 61\n", header), 9),
+        ("VERSION=3\nformat=base64\n".to_owned(), 2),
+    ];
+    for (text, line) in cases {
+        match load(&text) {
+            Err(Error::Dump { line: at, .. }) => assert_eq!(at, line, "{}", text),
+            other => panic!("expected a dump error, got {:?}", other),
+        }
+    }
+}
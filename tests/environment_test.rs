@@ -0,0 +1,285 @@
+use std::ops::Bound;
+
+use lmdb_zero_typed::*;
+
+#[derive(Debug)]
+enum AppError {
+    Lmdb(#[allow(dead_code)] Error),
+    Rejected,
+}
+
+impl From<Error> for AppError {
+    fn from(err: Error) -> Self {
+        AppError::Lmdb(err)
+    }
+}
+
+#[test]
+fn test_read_write() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .max_readers(64)
+        .flags(lmdb_zero::open::NOTLS)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = env
+        .open_db::<str, Ordered<u64>, LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+
+    let put_flags = lmdb_zero::put::Flags::empty();
+
+    // Committed on Ok.
+    env.write(|txn, access| -> Result<(), Error> {
+        access.put(&db, "a", &1, put_flags)?;
+        let mut c = txn.cursor(&db)?;
+        c.put(access, "b", &2, put_flags)
+    })
+    .unwrap();
+
+    // Aborted on Err.
+    let result = env.write(|_, access| {
+        access.put(&db, "a", &100, put_flags)?;
+        access.put(&db, "c", &3, put_flags)?;
+        Err::<(), _>(AppError::Rejected)
+    });
+    assert!(matches!(result, Err(AppError::Rejected)));
+
+    let sum = env
+        .read(|txn, access| -> Result<u64, Error> {
+            assert!(access.get(&db, "c").to_opt()?.is_none());
+            let mut c = txn.cursor(&db)?;
+            c.range(access, ..)?.map(|kv| Ok(kv?.1)).sum()
+        })
+        .unwrap();
+    assert_eq!(sum, 3);
+
+    let count = env
+        .write(|txn, _| -> Result<usize, Error> { Ok(txn.db_stat(&db)?.entries) })
+        .unwrap();
+    assert_eq!(count, 2);
+}
+
+#[test]
+fn test_write_resizing() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(64 * 1024)
+        .max_dbs(5)
+        .max_readers(64)
+        .flags(lmdb_zero::open::NOTLS)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = env
+        .open_db::<Ordered<u32>, [u8], LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+
+    let fill = |access: &mut WriteAccessor| -> Result<(), Error> {
+        for i in 0..64 {
+            access.put(&db, &i, &[7u8; 4096][..], lmdb_zero::put::Flags::empty())?;
+        }
+        Ok(())
+    };
+
+    let initial = env.info().unwrap().mapsize;
+    assert!(matches!(
+        env.write(|_, access| fill(access)),
+        Err(Error::MapFull(_))
+    ));
+
+    let capped = ResizePolicy {
+        growth: MapGrowth::Step(16 * 1024),
+        max_size: Some(initial + 32 * 1024),
+    };
+    assert!(matches!(
+        env.write_resizing(&capped, |_, access| fill(access)),
+        Err(Error::MapFull(_))
+    ));
+    assert_eq!(env.info().unwrap().mapsize, initial + 32 * 1024);
+
+    let mut attempts = 0;
+    env.write_resizing(&ResizePolicy::default(), |_, access| {
+        attempts += 1;
+        fill(access)
+    })
+    .unwrap();
+    assert!(attempts > 1);
+    assert!(env.info().unwrap().mapsize > initial + 32 * 1024);
+
+    let count = env
+        .read(|txn, _| -> Result<usize, Error> { Ok(txn.db_stat(&db)?.entries) })
+        .unwrap();
+    assert_eq!(count, 64);
+
+    assert_eq!(capped.next_size(initial), Some(initial + 16 * 1024));
+    assert_eq!(capped.next_size(initial + 32 * 1024), None);
+    assert_eq!(ResizePolicy::default().next_size(4096), Some(8192));
+}
+
+#[test]
+fn test_nested_transactions() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(64 * 1024)
+        .max_dbs(5)
+        .max_readers(64)
+        .flags(lmdb_zero::open::NOTLS)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = env
+        .open_db::<Ordered<u32>, [u8], LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+
+    let fill = |access: &mut WriteAccessor| -> Result<(), Error> {
+        for i in 0..64 {
+            access.put(&db, &i, &[7u8; 4096][..], lmdb_zero::put::Flags::empty())?;
+        }
+        Ok(())
+    };
+    let count = || env.read(|txn, _| -> Result<usize, Error> { Ok(txn.db_stat(&db)?.entries) });
+
+    // A resize cannot happen inside a transaction of the same thread.
+    let nested =
+        env.read(|_, _| env.write_resizing(&ResizePolicy::default(), |_, access| fill(access)));
+    assert!(matches!(nested, Err(Error::MapFull(_))));
+
+    // A nested read does not wait behind a resize queued on another thread.
+    std::thread::scope(|scope| {
+        let resizer = env
+            .read(|_, _| -> Result<_, Error> {
+                let resizer = scope.spawn(|| {
+                    env.write_resizing(&ResizePolicy::default(), |_, access| fill(access))
+                });
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                assert_eq!(count()?, 0);
+                Ok(resizer)
+            })
+            .unwrap();
+        resizer.join().unwrap().unwrap();
+    });
+    assert_eq!(count().unwrap(), 64);
+}
+
+#[test]
+fn test_registry() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+    assert!(matches!(
+        TypedEnv::builder().open(tmp.path()),
+        Err(Error::AlreadyOpen(_))
+    ));
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = env
+        .open_db::<str, Ordered<u64>, LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+    env.open_db::<str, str, LmdbLayoutDupsort>(Some("tree2"), &opts)
+        .unwrap();
+
+    assert!(matches!(
+        env.open_db::<str, str, LmdbLayoutDefault>(Some("tree1"), &opts),
+        Err(Error::TypeConflict { .. })
+    ));
+    assert!(matches!(
+        env.open_db::<str, str, LmdbLayoutDefault>(Some("tree2"), &opts),
+        Err(Error::TypeConflict { .. })
+    ));
+    drop(db);
+    env.open_db::<str, Ordered<u64>, LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+
+    drop(env);
+    let env = TypedEnv::builder().max_dbs(5).open(tmp.path()).unwrap();
+
+    // The path stays registered while the unwrapped environment lives.
+    let raw = env.into_lmdb();
+    assert!(matches!(
+        TypedEnv::builder().open(tmp.path()),
+        Err(Error::AlreadyOpen(_))
+    ));
+    drop(raw);
+
+    // With NOSUBDIR the file does not exist before the first open, and
+    // another spelling of it is still recognised.
+    std::fs::create_dir(tmp.path().join("sub")).unwrap();
+    let file = tmp.path().join("other.mdb");
+    let _env = TypedEnv::builder()
+        .flags(lmdb_zero::open::NOSUBDIR)
+        .open(tmp.path().join("sub").join("..").join("other.mdb"))
+        .unwrap();
+    assert!(matches!(
+        TypedEnv::builder()
+            .flags(lmdb_zero::open::NOSUBDIR)
+            .open(&file),
+        Err(Error::AlreadyOpen(_))
+    ));
+}
+
+#[test]
+fn test_owned_reads() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = env
+        .open_db::<str, str, LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+
+    env.write(|_, access| -> Result<(), Error> {
+        let put_flags = lmdb_zero::put::Flags::empty();
+        access.put(&db, "a", "one", put_flags)?;
+        access.put(&db, "b", "two", put_flags)?;
+        access.put(&db, "c", "three", put_flags)
+    })
+    .unwrap();
+
+    let (value, entries, all) = env
+        .read(|txn, access| -> Result<_, Error> {
+            let value: String = access.get_owned(&db, "b")?;
+            let mut c = txn.cursor(&db)?;
+            let entries = c
+                .range(access, (Bound::Included("b"), Bound::Unbounded))?
+                .owned()
+                .collect::<Result<Vec<_>, _>>()?;
+            let all = CursorIter::new(
+                lmdb_zero::MaybeOwned::Owned(txn.cursor(&db)?),
+                access,
+                |c, a| c.first(a),
+                Cursor::next,
+            )?
+            .owned()
+            .collect::<Result<Vec<_>, _>>()?;
+            Ok((value, entries, all))
+        })
+        .unwrap();
+
+    assert_eq!(value, "two");
+    assert_eq!(
+        entries,
+        [
+            ("b".to_owned(), "two".to_owned()),
+            ("c".to_owned(), "three".to_owned())
+        ]
+    );
+    assert_eq!(all.len(), 3);
+}
@@ -0,0 +1,141 @@
+use lmdb_zero_typed::*;
+
+#[test]
+fn test_import() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+    let runs = tempdir::TempDir::new("unit.test.runs").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(10_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = env
+        .open_db::<Ordered<u32>, str, LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+
+    // Every key appears twice, and the small budget spills many runs.
+    let items = (0..1000u32)
+        .map(|i| (i * 7919 % 500, format!("v{}", i)))
+        .collect::<Vec<_>>();
+    let importer = Importer::new(&db)
+        .memory_budget(1000)
+        .temp_dir(runs.path())
+        .chunk_size(64);
+
+    match importer.import(items.iter().map(|(k, v)| (*k, v.as_str()))) {
+        Err(Error::DuplicateKey { db, .. }) => assert_eq!(db.as_deref(), Some("tree1")),
+        other => panic!("expected a duplicate key error, got {:?}", other),
+    }
+    assert_eq!(std::fs::read_dir(runs.path()).unwrap().count(), 0);
+
+    let count = importer
+        .duplicates(Duplicates::KeepLast)
+        .import(items.iter().map(|(k, v)| (*k, v.as_str())))
+        .unwrap();
+    assert_eq!(count, 500);
+    assert_eq!(std::fs::read_dir(runs.path()).unwrap().count(), 0);
+
+    let entries = env
+        .read(|txn, access| -> Result<_, Error> {
+            txn.iter(&db, access)?
+                .owned()
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap();
+    let expected = (0..500u32)
+        .map(|k| {
+            let last = (0..1000).rev().find(|i| i * 7919 % 500 == k).unwrap();
+            (k, format!("v{}", last))
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(entries, expected);
+}
+
+#[test]
+fn test_import_dupsort() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE | lmdb_zero::db::DUPSORT);
+    let db = env
+        .open_db::<str, str, LmdbLayoutDupsort>(Some("tree1"), &opts)
+        .unwrap();
+
+    // Only the repeated ("b", "1") pair is a duplicate.
+    let items = [("b", "2"), ("a", "9"), ("b", "1"), ("a", "3"), ("b", "1")];
+    let count = Importer::new(&db)
+        .memory_budget(0)
+        .duplicates(Duplicates::KeepFirst)
+        .import(items)
+        .unwrap();
+    assert_eq!(count, 4);
+
+    let entries = env
+        .read(|txn, access| -> Result<_, Error> {
+            txn.iter(&db, access)?
+                .owned()
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap();
+    let expected = [("a", "3"), ("a", "9"), ("b", "1"), ("b", "2")];
+    assert_eq!(entries, expected.map(|(k, v)| (k.to_owned(), v.to_owned())));
+}
+
+#[test]
+fn test_import_non_empty() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = env
+        .open_db::<Ordered<u32>, str, LmdbLayoutDefault>(Some("tree1"), &opts)
+        .unwrap();
+    env.write(|_, access| -> Result<(), Error> {
+        for k in 0..10u32 {
+            access.put(&db, &k, "old", lmdb_zero::put::Flags::empty())?;
+        }
+        Ok(())
+    })
+    .unwrap();
+
+    let importer = Importer::new(&db).memory_budget(0).chunk_size(2);
+    let count = importer
+        .import((10..20u32).rev().map(|k| (k, "new")))
+        .unwrap();
+    assert_eq!(count, 10);
+
+    // The smallest record is checked before any chunk is committed.
+    match importer.import([(30u32, "late"), (31, "late"), (19, "late")]) {
+        Err(Error::OutOfOrder { db, position, key }) => {
+            assert_eq!(db.as_deref(), Some("tree1"));
+            assert_eq!(position, 0);
+            assert_eq!(key, 19u32.to_be_bytes());
+        }
+        other => panic!("expected an out of order error, got {:?}", other),
+    }
+
+    let entries = env
+        .read(|txn, access| -> Result<_, Error> {
+            txn.iter(&db, access)?
+                .owned()
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap();
+    let expected = (0..20u32)
+        .map(|k| (k, if k < 10 { "old" } else { "new" }.to_owned()))
+        .collect::<Vec<_>>();
+    assert_eq!(entries, expected);
+}
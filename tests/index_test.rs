@@ -0,0 +1,155 @@
+use std::ops::Bound;
+
+use lmdb_zero_typed::*;
+
+fn domain(email: &str) -> &str {
+    email.split_once('@').map_or("", |(_, domain)| domain)
+}
+
+#[test]
+fn test_indexed_database() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let mut users = IndexedDatabase::new(
+        env.open_db::<str, str, LmdbLayoutDefault>(Some("users"), &opts)
+            .unwrap(),
+    );
+    let by_domain = users.add_index(
+        env.open_db::<str, str, LmdbLayoutDupsort>(Some("users_by_domain"), &opts)
+            .unwrap(),
+        |email: &str| domain(email).to_owned(),
+    );
+    let by_len = users.add_index(
+        env.open_db::<Ordered<u64>, str, LmdbLayoutDupsort>(Some("users_by_len"), &opts)
+            .unwrap(),
+        |email: &str| email.len() as u64,
+    );
+
+    let put_flags = lmdb_zero::put::Flags::empty();
+    env.write(|_, access| -> Result<(), Error> {
+        users.put(access, "alice", "alice@x.org", put_flags)?;
+        users.put(access, "bob", "bob@y.org", put_flags)?;
+        users.put(access, "carol", "carol@x.org", put_flags)
+    })
+    .unwrap();
+
+    let lookup = |name: &str| -> Vec<String> {
+        env.read(|txn, access| -> Result<_, Error> {
+            by_domain
+                .get(txn, access, name)?
+                .map(|key| key.map(str::to_owned))
+                .collect()
+        })
+        .unwrap()
+    };
+    assert_eq!(lookup("x.org"), ["alice", "carol"]);
+    assert_eq!(lookup("y.org"), ["bob"]);
+
+    // Overwriting moves the index entry.
+    env.write(|_, access| users.put(access, "alice", "alice@y.org", put_flags))
+        .unwrap();
+    assert_eq!(lookup("x.org"), ["carol"]);
+    assert_eq!(lookup("y.org"), ["alice", "bob"]);
+
+    env.write(|_, access| users.del_key(access, "bob")).unwrap();
+    assert_eq!(lookup("y.org"), ["alice"]);
+    assert!(env
+        .write(|_, access| users.del_key(access, "bob"))
+        .unwrap_err()
+        .is_not_found());
+
+    // An aborted write leaves primary and indexes untouched.
+    let result = env.write(|_, access| -> Result<(), Error> {
+        users.put(access, "dave", "dave@x.org", put_flags)?;
+        Err(Error::NotFound(Context::default()))
+    });
+    assert!(result.is_err());
+    assert_eq!(lookup("x.org"), ["carol"]);
+
+    let by_length = env
+        .read(|txn, access| -> Result<_, Error> {
+            assert_eq!(users.get(access, "alice")?, "alice@y.org");
+            by_len
+                .range(txn, access, (Bound::Included(11), Bound::Unbounded))?
+                .owned()
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap();
+    assert_eq!(
+        by_length,
+        [(11, "alice".to_owned()), (11, "carol".to_owned())]
+    );
+}
+
+#[test]
+fn test_unique_index() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let mut users = IndexedDatabase::new(
+        env.open_db::<str, str, LmdbLayoutDefault>(Some("users"), &opts)
+            .unwrap(),
+    );
+    let by_email = users.add_unique_index(
+        env.open_db::<str, str, LmdbLayoutDefault>(Some("users_by_email"), &opts)
+            .unwrap(),
+        |email: &str| email.to_owned(),
+    );
+
+    let put_flags = lmdb_zero::put::Flags::empty();
+    env.write(|_, access| -> Result<(), Error> {
+        users.put(access, "alice", "alice@x.org", put_flags)?;
+        users.put(access, "bob", "bob@x.org", put_flags)?;
+        // Rewriting a record with its own index key is not a violation.
+        users.put(access, "alice", "alice@x.org", put_flags)
+    })
+    .unwrap();
+
+    let result = env.write(|_, access| users.put(access, "carol", "alice@x.org", put_flags));
+    match result {
+        Err(Error::UniqueViolation { index, key }) => {
+            assert_eq!(index.as_deref(), Some("users_by_email"));
+            assert_eq!(key, b"alice@x.org");
+        }
+        other => panic!("expected a unique violation, got {:?}", other),
+    }
+
+    // A violation inside a child transaction leaves the parent usable.
+    let mut txn = WriteTransaction::new(&*env).unwrap();
+    assert!(matches!(
+        users.put_atomic(&mut txn, "bob", "alice@x.org", put_flags),
+        Err(Error::UniqueViolation { .. })
+    ));
+    users
+        .put_atomic(&mut txn, "alice", "alice@y.org", put_flags)
+        .unwrap();
+    users
+        .put_atomic(&mut txn, "bob", "alice@x.org", put_flags)
+        .unwrap();
+    txn.commit().unwrap();
+
+    env.read(|_, access| -> Result<(), Error> {
+        assert!(users.get(access, "carol").unwrap_err().is_not_found());
+        assert_eq!(by_email.get(access, "alice@x.org")?, "bob");
+        assert_eq!(by_email.get(access, "alice@y.org")?, "alice");
+        assert!(by_email
+            .get(access, "bob@x.org")
+            .unwrap_err()
+            .is_not_found());
+        Ok(())
+    })
+    .unwrap();
+}
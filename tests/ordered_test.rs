@@ -0,0 +1,127 @@
+use lmdb_zero_typed::*;
+
+fn assert_sorted<T: OrderedKey + std::fmt::Debug>(items: &[T]) {
+    let encoded: Vec<_> = items
+        .iter()
+        .map(|item| <Ordered<T>>::encode(item).unwrap().into_owned())
+        .collect();
+    for (i, w) in encoded.windows(2).enumerate() {
+        assert!(w[0] < w[1], "{:?} !< {:?}", items[i], items[i + 1]);
+    }
+    for (item, bytes) in items.iter().zip(&encoded) {
+        let decoded = <Ordered<T>>::decode(bytes).unwrap();
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", item));
+    }
+}
+
+#[test]
+fn test_ordered_encoding() {
+    assert_sorted(&[0u32, 1, 255, 256, 65536, u32::MAX]);
+    assert_sorted(&[i64::MIN, -65536, -256, -1, 0, 1, 255, i64::MAX]);
+    assert_sorted(&[
+        f64::NEG_INFINITY,
+        -1e300,
+        -1.5,
+        -0.0,
+        0.0,
+        f64::MIN_POSITIVE,
+        1.5,
+        1e300,
+        f64::INFINITY,
+    ]);
+    assert_sorted(&[false, true]);
+    assert_sorted(&[
+        String::new(),
+        "\0".to_owned(),
+        "\0\0".to_owned(),
+        "\0a".to_owned(),
+        "a".to_owned(),
+        "a\0".to_owned(),
+        "a\0\u{ff}".to_owned(),
+        "ab".to_owned(),
+        "b".to_owned(),
+    ]);
+    assert_sorted(&[vec![], vec![0], vec![0, 0xff], vec![1], vec![0xff, 0xff]]);
+    assert_sorted(&[
+        ("a".to_owned(), u8::MAX),
+        ("a\0".to_owned(), 0),
+        ("b".to_owned(), 0),
+    ]);
+    assert_sorted(&[
+        (-1i32, 2u16, -0.5f32),
+        (-1, 2, 0.5),
+        (-1, 3, 0.0),
+        (0, 0, 0.0),
+    ]);
+
+    assert!(matches!(
+        <Ordered<(u32, String)>>::decode(&[0, 0, 0, 1, b'a']),
+        Err(lmdb_zero::Error::ValRejected(_))
+    ));
+    assert!(matches!(
+        <Ordered<u16>>::decode(&[0, 0, 0]),
+        Err(lmdb_zero::Error::ValRejected(_))
+    ));
+}
+
+#[test]
+fn test_ordered_database() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = unsafe {
+        let mut eb = lmdb_zero::EnvBuilder::new().unwrap();
+        eb.set_mapsize(1_000_000).unwrap();
+        eb.set_maxdbs(5).unwrap();
+
+        eb.set_maxreaders(64).unwrap();
+
+        eb.open(&tmp.path().to_string_lossy(), lmdb_zero::open::NOTLS, 0o600)
+            .unwrap()
+    };
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = Database::<Ordered<(String, i64)>, str, LmdbLayoutDefault>::open(
+        &env,
+        Some("tree1"),
+        &opts,
+    )
+    .unwrap();
+
+    let put_flags = lmdb_zero::put::Flags::empty();
+
+    let txn = WriteTransaction::new(&env).unwrap();
+    let mut access = txn.access();
+    for (user, t) in [("bob", 7), ("alice", -3), ("bob", -300), ("alice", 1000)] {
+        let value = format!("{}@{}", user, t);
+        access
+            .put(&db, &(user.to_owned(), t), &value, put_flags)
+            .unwrap();
+    }
+
+    let mut c = txn.cursor(&db).unwrap();
+    assert_eq!(
+        c.first(&access).unwrap(),
+        (("alice".to_owned(), -3), "alice@-3")
+    );
+    assert_eq!(
+        c.next(&access).unwrap(),
+        (("alice".to_owned(), 1000), "alice@1000")
+    );
+    assert_eq!(
+        c.next(&access).unwrap(),
+        (("bob".to_owned(), -300), "bob@-300")
+    );
+    assert_eq!(c.next(&access).unwrap(), (("bob".to_owned(), 7), "bob@7"));
+    assert!(c.next(&access).to_opt().unwrap().is_none());
+
+    assert_eq!(
+        c.seek_range_k(&access, &("alice".to_owned(), 0)).unwrap(),
+        (("alice".to_owned(), 1000), "alice@1000")
+    );
+    assert_eq!(
+        c.seek_range_k(&access, &("alice".to_owned(), i64::MAX))
+            .unwrap(),
+        (("bob".to_owned(), -300), "bob@-300")
+    );
+    assert_eq!(access.get(&db, &("bob".to_owned(), 7)).unwrap(), "bob@7");
+}
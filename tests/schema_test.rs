@@ -0,0 +1,80 @@
+use lmdb_zero::traits::*;
+
+use lmdb_zero_typed::*;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Id(u32);
+
+unsafe impl LmdbRaw for Id {}
+
+#[derive(Schema)]
+pub struct Tables<'e> {
+    #[db(name = "users")]
+    users: Database<'e, str, str, LmdbLayoutDefault>,
+    #[db(name = "user_tags", dupsort)]
+    user_tags: Database<'e, str, str, LmdbLayoutDupsort>,
+    #[db(integer_key, dupsort, dupfixed, integer_dup)]
+    postings: Database<'e, Id, Id, LmdbLayoutDupfixed>,
+}
+
+#[test]
+fn test_schema() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let tables = Tables::open_all(&env).unwrap();
+    let _: &UsersDb = &tables.users;
+    let _: &UserTagsDb = &tables.user_tags;
+    assert!(matches!(
+        env.open_db::<str, [u8], LmdbLayoutDefault>(
+            Some("users"),
+            &lmdb_zero::DatabaseOptions::defaults()
+        ),
+        Err(Error::TypeConflict { .. })
+    ));
+
+    let txn = ReadTransaction::new(env.as_lmdb()).unwrap();
+    assert_eq!(
+        txn.db_flags(&tables.postings).unwrap(),
+        lmdb_zero::db::INTEGERKEY
+            | lmdb_zero::db::DUPSORT
+            | lmdb_zero::db::DUPFIXED
+            | lmdb_zero::db::INTEGERDUP
+    );
+    assert_eq!(
+        txn.db_flags(&tables.user_tags).unwrap(),
+        lmdb_zero::db::DUPSORT
+    );
+    drop(txn);
+
+    env.write(|txn, access| -> Result<(), Error> {
+        let put_flags = lmdb_zero::put::Flags::empty();
+        access.put(&tables.users, "alice", "Alice", put_flags)?;
+        access.put(&tables.user_tags, "alice", "admin", put_flags)?;
+        access.put(&tables.user_tags, "alice", "dev", put_flags)?;
+        let mut c: PostingsCursor = tables.postings_cursor(txn)?;
+        c.put_multiple(access, &Id(7), &[Id(1), Id(2), Id(3)], put_flags)?;
+        Ok(())
+    })
+    .unwrap();
+
+    env.read(|txn, access| -> Result<(), Error> {
+        assert_eq!(access.get(&tables.users, "alice")?, "Alice");
+        let mut c: UserTagsCursor = tables.user_tags_cursor(txn)?;
+        assert_eq!(c.dups(access, "alice")?.count(), 2);
+        let mut c = tables.postings_cursor(txn)?;
+        assert_eq!(c.pages(access, &Id(7))?.values().count(), 3);
+        Ok(())
+    })
+    .unwrap();
+
+    // Reopening goes through the same flag and layout checks.
+    drop(tables);
+    Tables::open_all(&env).unwrap();
+}
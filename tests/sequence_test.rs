@@ -0,0 +1,91 @@
+use lmdb_zero_typed::*;
+
+#[test]
+fn test_sequence() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let meta = env
+        .open_db::<str, Ordered<u64>, LmdbLayoutDefault>(Some("meta"), &opts)
+        .unwrap();
+    let users = Sequence::new(&meta, "users");
+    let posts = Sequence::new(&meta, "posts");
+
+    let ids = env
+        .write(|_, access| -> Result<_, Error> {
+            Ok((
+                users.next(access)?,
+                users.next(access)?,
+                users.next_n(access, 3)?,
+                posts.next(access)?,
+            ))
+        })
+        .unwrap();
+    assert_eq!(ids, (0, 1, 2..5, 0));
+
+    // Values taken by an aborted transaction are handed out again.
+    let result = env.write(|_, access| -> Result<(), Error> {
+        users.next_n(access, 10)?;
+        Err(Error::NotFound(Context::default()))
+    });
+    assert!(result.is_err());
+    assert_eq!(env.read(|_, access| users.peek(access)).unwrap(), 5);
+
+    env.write(|_, access| {
+        assert_eq!(users.next(access)?, 5);
+        users.reset(access)?;
+        users.next(access)
+    })
+    .unwrap();
+    assert_eq!(env.read(|_, access| users.peek(access)).unwrap(), 1);
+    assert_eq!(env.read(|_, access| posts.peek(access)).unwrap(), 1);
+}
+
+#[test]
+fn test_sequence_overflow() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = TypedEnv::builder()
+        .map_size(1_000_000)
+        .max_dbs(5)
+        .open(tmp.path())
+        .unwrap();
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let meta = env
+        .open_db::<str, Ordered<u64>, LmdbLayoutDefault>(Some("meta"), &opts)
+        .unwrap();
+    let ids = Sequence::new(&meta, "ids");
+
+    env.write(|_, access| {
+        access.put(
+            &meta,
+            "ids",
+            &(u64::MAX - 3),
+            lmdb_zero::put::Flags::empty(),
+        )
+    })
+    .unwrap();
+    let result = env.write(|_, access| {
+        assert_eq!(ids.next_n(access, 2)?, u64::MAX - 3..u64::MAX - 1);
+        assert_eq!(ids.next(access)?, u64::MAX - 1);
+        ids.next_n(access, 2)
+    });
+    match result {
+        Err(Error::Overflow(context)) => {
+            assert_eq!(context.op, Some("next_n"));
+            assert_eq!(context.db.as_deref(), Some("meta"));
+        }
+        other => panic!("expected an overflow error, got {:?}", other),
+    }
+    assert_eq!(
+        env.read(|_, access| ids.peek(access)).unwrap(),
+        u64::MAX - 3
+    );
+}
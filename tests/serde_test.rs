@@ -0,0 +1,102 @@
+#![cfg(any(
+    feature = "bincode",
+    feature = "postcard",
+    feature = "json",
+    feature = "cbor"
+))]
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use lmdb_zero_typed::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    retries: u32,
+    weights: Vec<f64>,
+    tags: BTreeMap<String, i64>,
+}
+
+fn config() -> Config {
+    let mut tags = BTreeMap::new();
+    tags.insert("region".to_owned(), -4);
+    tags.insert("tier".to_owned(), 2);
+    Config {
+        name: "primary".to_owned(),
+        retries: 3,
+        weights: vec![0.5, 1.25],
+        tags,
+    }
+}
+
+fn roundtrip<F: SerdeFormat>() {
+    let tmp = tempdir::TempDir::new("unit.test").unwrap();
+
+    let env = unsafe {
+        let mut eb = lmdb_zero::EnvBuilder::new().unwrap();
+        eb.set_mapsize(1_000_000).unwrap();
+        eb.set_maxdbs(5).unwrap();
+
+        eb.set_maxreaders(64).unwrap();
+
+        eb.open(&tmp.path().to_string_lossy(), lmdb_zero::open::NOTLS, 0o600)
+            .unwrap()
+    };
+
+    let opts = lmdb_zero::DatabaseOptions::new(lmdb_zero::db::CREATE);
+    let db = Database::<str, Serde<F, Config>, LmdbLayoutDefault>::open(&env, Some("tree1"), &opts)
+        .unwrap();
+    let raw = Database::<str, [u8], LmdbLayoutDefault>::open(&env, Some("tree2"), &opts).unwrap();
+
+    let put_flags = lmdb_zero::put::Flags::empty();
+
+    {
+        let txn = WriteTransaction::new(&env).unwrap();
+        {
+            let mut access = txn.access();
+            access.put(&db, "a", &config(), put_flags).unwrap();
+            access.put(&raw, "junk", &[0xff; 3], put_flags).unwrap();
+        }
+        txn.commit().unwrap();
+    }
+
+    let txn = ReadTransaction::new(&env).unwrap();
+    let access = txn.access();
+    assert_eq!(access.get(&db, "a").unwrap(), config());
+    assert!(access.get(&db, "b").to_opt().unwrap().is_none());
+
+    let mut c = txn.cursor(&db).unwrap();
+    assert_eq!(c.first(&access).unwrap(), ("a", config()));
+
+    let junk = access.get(&raw, "junk").unwrap();
+    assert!(matches!(
+        <Serde<F, Config>>::decode(junk),
+        Err(lmdb_zero::Error::ValRejected(_))
+    ));
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn test_bincode() {
+    roundtrip::<Bincode>();
+}
+
+#[cfg(feature = "postcard")]
+#[test]
+fn test_postcard() {
+    roundtrip::<Postcard>();
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json() {
+    roundtrip::<Json>();
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn test_cbor() {
+    roundtrip::<Cbor>();
+}